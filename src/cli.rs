@@ -1,55 +1,564 @@
 use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 use clap::Parser;
 use reqwest::{Method, StatusCode};
 use reqwest::header::{HeaderName, HeaderValue};
 use reqwest::Url;
 
-use crate::filters::{FilterBody, FilterContentLength};
+use crate::filters::{BodyHash, FilterBody, FilterContentLength, FilterLineCount, FilterWordCount, MatchMode, StatusCodeMatcher, TimeRange};
+use crate::fuzz::delay::JitterDistribution;
+use crate::fuzz::output::{OutputFormat, SplitBy};
+use crate::fuzz::progress_bar::ProgressStream;
+use crate::fuzz::schedule::Schedule;
+use crate::fuzz::stop::StopPattern;
+use crate::probe::FUZZ;
+use crate::probe::connect_to::ConnectTo;
+use crate::probe::cookies::Cookie;
+use crate::probe::digest::DigestAuth;
+use crate::probe::replace::Replacement;
+use crate::probe::signing::HmacPart;
+use crate::words::{Mutation, Order, WordSub};
 
 /// Imitation of Gobuster/ffuf in Rust.
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
-    /// The target URL
-    #[arg(short, long)]
-    pub url: Url,
+    /// The target URL; repeat to scan several hosts
+    #[arg(short, long, required = true)]
+    pub url: Vec<Url>,
 
-    /// Path to the wordlist.
-    #[arg(short, long)]
-    pub wordlist: std::path::PathBuf,
+    /// Scan each URL on each of these ports instead of the one it names, e.g. 80,443,8080
+    #[arg(long, value_delimiter = ',')]
+    pub ports: Vec<u16>,
+
+    /// Path to the wordlist; prefix with a keyword, e.g. FUZ2Z:values.txt, to fuzz several positions with every combination of their lists
+    #[arg(short, long, value_parser = parse_wordlist, required_unless_present = "input_cmd")]
+    pub wordlist: Vec<(String, PathBuf)>,
+
+    /// Read the FUZZ wordlist from the output of a shell command, e.g. "seq 1 1000"
+    #[arg(long)]
+    pub input_cmd: Option<String>,
 
     /// File extensions to search for, e.g. json,xml
     #[arg(short = 'x', long, value_delimiter = ',', default_value = "")]
     pub extensions: Vec<String>,
 
+    /// Also try each word with the backup suffixes .bak, .old, .orig, .save, .swp, .tmp, ~ and .1
+    #[arg(long)]
+    pub backups: bool,
+
+    /// Megabytes that words held in memory, such as those seeded from robots.txt or printed by --input-cmd, may take before the scan aborts
+    #[arg(long, value_name = "MB", default_value_t = 512)]
+    pub max_wordlist_memory: usize,
+
+    /// Rewrite each word with a sed-like substitution, e.g. 's/_/-/g'; \1 inserts a capture group
+    #[arg(long, alias = "wordlist-transform-regex", value_name = "s/FIND/REPLACE/")]
+    pub word_sub: Vec<WordSub>,
+
+    /// Order in which words are probed: file, shortest-first or longest-first; the latter two buffer the wordlist
+    #[arg(long, default_value = "file")]
+    pub order: Order,
+
+    /// Skip words shorter than this many characters, before extensions are added
+    #[arg(long, value_name = "CHARS")]
+    pub min_word_length: Option<usize>,
+
+    /// Skip words longer than this many characters, before extensions are added
+    #[arg(long, value_name = "CHARS")]
+    pub max_word_length: Option<usize>,
+
+    /// Decode wordlist lines that are not valid UTF-8 lossily instead of skipping them
+    #[arg(long)]
+    pub lossy_words: bool,
+
+    /// Iterate words in the same order on every run, sorting seeded words and mutations
+    #[arg(long)]
+    pub stable_order: bool,
+
+    /// Also try variants of each word: years (admin2024), leet (4dm1n) and separators (my_app for my-app)
+    #[arg(long, value_delimiter = ',', value_name = "RULES")]
+    pub mutations: Vec<Mutation>,
+
+    /// Only try extensions for words whose bare form is not filtered out
+    #[arg(long)]
+    pub smart_extensions: bool,
+
+    /// Probe the paths from each host's robots.txt and sitemap.xml before the wordlist
+    #[arg(long)]
+    pub seed_from_robots: bool,
+
     /// Use the following HTTP method
     #[arg(short, long, default_value = "GET")]
     pub method: Method,
 
+    /// Send GET requests asking for this method in the X-HTTP-Method-Override header
+    #[arg(long, value_name = "VERB")]
+    pub method_override: Option<Method>,
+
     /// Custom headers; use the format "Header1: Content1, Header2: Content2"
     #[arg(short = 'H', long, value_delimiter = ',', value_parser = parse_headers, required = false)]
     pub headers: Vec<(HeaderName, HeaderValue)>,
 
+    /// Authenticate with HTTP Basic; an Authorization header given with -H takes precedence
+    #[arg(long, value_name = "USER:PASS", conflicts_with = "bearer")]
+    pub basic_auth: Option<String>,
+
+    /// Authenticate with this bearer token; an Authorization header given with -H takes precedence
+    #[arg(long, value_name = "TOKEN")]
+    pub bearer: Option<String>,
+
+    /// Fuzz the value of this header with the FUZZ wordlist, e.g. X-Api-Version
+    #[arg(long, value_name = "NAME")]
+    pub fuzz_header: Option<HeaderName>,
+
+    /// Number of concurrent requests
+    #[arg(short, long, default_value_t = 10)]
+    pub threads: usize,
+
+    /// Cap on requests in flight, and so on connections open, at once across all threads; without it --threads is the
+    /// cap, and with fewer connections than threads the other threads wait for a free one
+    #[arg(long, value_name = "N")]
+    pub max_connections: Option<usize>,
+
+    /// Scan the wordlist again under every directory found, e.g. a 301 from /admin to /admin/
+    #[arg(long)]
+    pub recursive: bool,
+
+    /// How many directories below the URL --recursive descends
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    pub recursion_depth: usize,
+
+    /// Order in which queued directories are scanned: fifo (breadth-first) or lifo (depth-first)
+    #[arg(long, default_value = "fifo")]
+    pub schedule: Schedule,
+
+    /// Queue at most N directories for --recursive; past it, the one that would be scanned last is skipped
+    #[arg(long, value_name = "N")]
+    pub queue_capacity: Option<usize>,
+
+    /// Send at most this many requests per second across all threads; 0 is unlimited
+    #[arg(long, default_value_t = 0)]
+    pub rate: u32,
+
+    /// Seconds over which concurrency ramps up from 1 to the number of threads
+    #[arg(long, default_value_t = 0.0, value_parser = parse_seconds)]
+    pub ramp_up: f32,
+
+    /// Lower concurrency while server errors are frequent and restore it once they subside
+    #[arg(long)]
+    pub adaptive_concurrency: bool,
+
+    /// Cookie to send, NAME=VALUE; cookies set by responses are sent from then on. Can be repeated
+    #[arg(long, value_name = "NAME=VALUE")]
+    pub cookie: Vec<Cookie>,
+
+    /// Request body; keywords such as FUZZ are replaced like in the URL
+    #[arg(long)]
+    pub data: Option<String>,
+
+    /// Send each line of the FUZZ wordlist as the whole request body, e.g. for lists of JSON payloads
+    #[arg(long, alias = "body-from-each-line", conflicts_with = "data")]
+    pub body_per_line: bool,
+
+    /// Send the body with Transfer-Encoding: chunked instead of a Content-Length
+    #[arg(long, alias = "probe-chunked-transfer")]
+    pub chunked: bool,
+
+    /// Replace KEY with VALUE in the URL, headers and body; VALUE may be {{now}} or {{uuid}}. Can be repeated
+    #[arg(long = "replace", value_name = "KEY=VALUE")]
+    pub replacements: Vec<Replacement>,
+
+    /// Sign each request with HMAC-SHA256 and send the hex signature in this header
+    #[arg(long, requires = "hmac_secret")]
+    pub hmac_header: Option<HeaderName>,
+
+    /// Secret key for the HMAC signature
+    #[arg(long, requires = "hmac_header")]
+    pub hmac_secret: Option<String>,
+
+    /// Request parts covered by the HMAC signature, in order and joined by newlines
+    #[arg(long, value_delimiter = ',', default_value = "method,path,body")]
+    pub hmac_parts: Vec<HmacPart>,
+
+    /// Randomize the case of the words in the URL path of each request, e.g. /AdMiN
+    #[arg(long)]
+    pub random_case: bool,
+
+    /// Trust this PEM-encoded CA certificate besides the system ones. Can be repeated
+    #[arg(long, value_name = "PEM")]
+    pub ca_cert: Vec<PathBuf>,
+
+    /// Connect to TARGET_HOST:TARGET_PORT for requests to HOST:PORT, keeping the Host header and SNI. Can be repeated
+    #[arg(long, value_name = "HOST:PORT:TARGET_HOST:TARGET_PORT")]
+    pub connect_to: Vec<ConnectTo>,
+
+    /// Send requests through this HTTP(S) or SOCKS5 proxy, e.g. http://127.0.0.1:8080 for Burp or socks5://127.0.0.1:1080
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Resolve target hostnames through this DNS-over-HTTPS JSON endpoint, e.g. https://cloudflare-dns.com/dns-query
+    #[arg(long, value_name = "URL")]
+    pub doh: Option<Url>,
+
+    /// POST to this OAuth token endpoint for the access_token sent as bearer Authorization
+    #[arg(long, value_name = "URL")]
+    pub token_endpoint: Option<Url>,
+
+    /// Seconds between fetches of a new token from --token-endpoint
+    #[arg(long, value_name = "SECS", default_value_t = 300)]
+    pub token_refresh_interval: u64,
+
+    /// Answer Digest authentication challenges with these credentials, e.g. user:pass
+    #[arg(long)]
+    pub digest_auth: Option<DigestAuth>,
+
     /// Seconds of delay between requests, in seconds
-    #[arg(short, long, default_value_t = 0.0)]
+    #[arg(short, long, default_value_t = 0.0, value_parser = parse_seconds)]
     pub delay: f32,
 
-    /// Status code that will be ignored, e.g. 404,500
-    #[arg(long, value_delimiter = ',', default_value = "404")]
-    pub filter_status_codes: Vec<StatusCode>,
+    /// Seconds by which the delay randomly varies around its mean
+    #[arg(long, default_value_t = 0.0, value_parser = parse_seconds)]
+    pub jitter: f32,
+
+    /// How the jitter is distributed: uniform (within the jitter) or gaussian (jitter as standard deviation)
+    #[arg(long, default_value = "uniform")]
+    pub jitter_dist: JitterDistribution,
+
+    /// Seed for randomized behaviour, to make runs reproducible
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Follow up to this many redirects and report the final response; 0 reports redirects as they are
+    #[arg(long, value_name = "MAX", default_value_t = 0)]
+    pub follow_redirects: usize,
+
+    /// Keep only this many bytes of response headers, and report responses that send more as anomalies
+    #[arg(long, alias = "max-response-headers-bytes", value_name = "BYTES")]
+    pub max_header_bytes: Option<usize>,
+
+    /// Warn when a path a word makes is rewritten before it is sent; double slashes, e.g. `/admin//config`, always
+    /// reach the server, but dot segments and backslashes are resolved
+    #[arg(long, alias = "preserve-double-slashes")]
+    pub warn_path_rewrites: bool,
+
+    /// Seconds to wait for each response before skipping the word; 0 waits indefinitely
+    #[arg(long, value_name = "SECS", default_value_t = 10.0, value_parser = parse_seconds)]
+    pub timeout: f32,
+
+    /// Times to send a request again when it fails to connect or times out
+    #[arg(long, default_value_t = 0)]
+    pub retries: usize,
+
+    /// Milliseconds to wait before the first retry, doubled before each next one
+    #[arg(long, value_name = "MS", default_value_t = 500)]
+    pub retry_backoff: u64,
+
+    /// Also retry, within --retries, responses with these status codes, e.g. 502,503,504
+    #[arg(long, value_delimiter = ',')]
+    pub retry_status_codes: Vec<StatusCode>,
+
+    /// Give up on a word after spending this many seconds on it, across all attempts
+    #[arg(long, value_parser = parse_seconds)]
+    pub timeout_budget: Option<f32>,
+
+    /// Only scan hosts whose /favicon.ico has this Shodan-style (mmh3) hash
+    #[arg(long, allow_negative_numbers = true)]
+    pub match_favicon_hash: Option<i32>,
+
+    /// Abort unless the URL with an empty word responds with this content type, e.g. application/json
+    #[arg(long, value_name = "MIME")]
+    pub expect_content_type: Option<String>,
+
+    /// Check each host for a WAF or CDN before scanning, and warn when one is found
+    #[arg(long)]
+    pub detect_waf: bool,
+
+    /// Stop the scan once a hit's word or URL equals, or entirely matches the regex, this pattern
+    #[arg(long)]
+    pub stop_after_hit: Option<StopPattern>,
+
+    /// Status codes that will be ignored, e.g. 404,500, a range, e.g. 500-599, or a class, e.g. 4xx
+    #[arg(long, default_value = "404")]
+    pub filter_status_codes: StatusCodeMatcher,
+
+    /// Only report these status codes, e.g. 200,301,302; takes precedence over --filter-status-codes
+    #[arg(long, value_delimiter = ',')]
+    pub match_status_codes: Vec<StatusCode>,
 
     /// Content lengths that will be ignored, e.g. 20,300, or a range, e.g. 20-300
-    #[arg(long, default_value_t = FilterContentLength::Empty)]
-    pub filter_content_length: FilterContentLength,
+    #[arg(long, value_name = "LENGTHS")]
+    pub filter_content_length: Option<String>,
 
-    /// Ignore if text appears in the response body
-    #[arg(long, default_value_t = FilterBody::Empty)]
+    /// Report responses that satisfy all of the --match-* options given, or any one of them
+    #[arg(long, default_value = "all")]
+    pub match_mode: MatchMode,
+
+    /// Only report responses that set a cookie, or, given a NAME, a cookie whose name contains it
+    #[arg(long, value_name = "NAME", num_args = 0..=1, default_missing_value = "")]
+    pub match_set_cookie: Option<String>,
+
+    /// Only report these content lengths, e.g. 20,300, or a range, e.g. 20-300; --filter-content-length still applies
+    #[arg(long, value_name = "LENGTHS")]
+    pub match_content_length: Option<String>,
+
+    /// Word counts of the response body that will be ignored, e.g. 20,300, or a range, e.g. 20-300
+    #[arg(long, value_name = "COUNTS")]
+    pub filter_words: Option<String>,
+
+    /// Line counts of the response body that will be ignored, e.g. 20,300, or a range, e.g. 20-300
+    #[arg(long, value_name = "COUNTS")]
+    pub filter_lines: Option<String>,
+
+    /// Fail on lengths and counts that are not numbers in the filters above, instead of skipping them
+    #[arg(long)]
+    pub strict_filters: bool,
+
+    /// Ignore if text appears in the response body, or if it matches a regex given as re:PATTERN
+    #[arg(long, default_value = "")]
     pub filter_body: FilterBody,
 
-    /// Verbose output including response status code, content length, etc.
+    /// Ignore soft-404s: responses resembling the one to a random word, even when served as 200
+    #[arg(long)]
+    pub smart_404: bool,
+
+    /// Probe a few random words first and ignore responses of the same sizes, for servers that answer every path
+    #[arg(long)]
+    pub auto_calibrate: bool,
+
+    /// Probe this many words spread over the wordlist first and print the filter for the response most of them got
+    #[arg(long, value_name = "SAMPLE", num_args = 0..=1, default_missing_value = "50")]
+    pub two_pass: Option<usize>,
+
+    /// Run the scan after --two-pass, with its suggested filter applied
+    #[arg(long, requires = "two_pass")]
+    pub apply_suggested: bool,
+
+    /// Also probe registered /.well-known/ locations, such as security.txt and openid-configuration, before the wordlist
+    #[arg(long, alias = "probe-well-known")]
+    pub well_known: bool,
+
+    /// Probe each hit again with the case of its word flipped, and report whether the server is case-sensitive
+    #[arg(long, alias = "probe-case-variants-on-hit")]
+    pub confirm_case: bool,
+
+    /// Print the method, URL, headers and body of each request instead of sending it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Once the scan ends, send OPTIONS to each directory found and report the methods its Allow header lists
+    #[arg(long, alias = "probe-options-allow")]
+    pub enumerate_methods: bool,
+
+    /// Once the scan ends, try a built-in list of default user:pass pairs with HTTP Basic on each hit that answered 401, and report the ones accepted
+    #[arg(long, alias = "probe-default-credentials")]
+    pub default_creds: bool,
+
+    /// Report only the first path with a given ETag; paths that share one serve the same resource
+    #[arg(long, alias = "filter-by-etag")]
+    pub dedupe_etag: bool,
+
+    /// A word known to exist; with --canary-bad, only responses closer to its response than the bad one's are reported
+    #[arg(long, value_name = "WORD", requires = "canary_bad")]
+    pub canary_good: Option<String>,
+
+    /// A word known not to exist, compared against with --canary-good
+    #[arg(long, value_name = "WORD", requires = "canary_good")]
+    pub canary_bad: Option<String>,
+
+    /// Ignore trailing whitespace when filtering on length and body; reported sizes stay exact
+    #[arg(long)]
+    pub trailing_newline_normalization: bool,
+
+    /// Match --filter-body against bodies with runs of whitespace collapsed to one space
+    #[arg(long, alias = "normalize-whitespace-in-body-filter")]
+    pub normalize_whitespace: bool,
+
+    /// Ignore responses whose body has this hex SHA-256 hash. Can be repeated
+    #[arg(long, value_name = "HEX")]
+    pub filter_hash: Vec<BodyHash>,
+
+    /// Filter on the Content-Length header only and report responses whose body size differs from it
+    #[arg(long)]
+    pub content_length_from_header_only: bool,
+
+    /// Only report responses whose time in milliseconds is in this window, e.g. >5000, <100 or 1000-2000
+    #[arg(long, allow_hyphen_values = true)]
+    pub match_response_time: Option<TimeRange>,
+
+    /// Ignore responses that took longer than this many milliseconds
+    #[arg(long, value_name = "MS")]
+    pub filter_time_gt: Option<u64>,
+
+    /// Ignore responses that took less than this many milliseconds
+    #[arg(long, value_name = "MS")]
+    pub filter_time_lt: Option<u64>,
+
+    /// Only report responses whose time to first byte in milliseconds is in this window, e.g. >2000
+    #[arg(long, allow_hyphen_values = true)]
+    pub match_ttfb: Option<TimeRange>,
+
+    /// Ignore responses whose time to first byte in milliseconds is in this window, e.g. <50
+    #[arg(long, allow_hyphen_values = true)]
+    pub filter_ttfb: Option<TimeRange>,
+
+    /// Verbose output: -v adds status code and size, -vv timing and headers, -vvv a body snippet
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Also write hits into one file per status in this directory, e.g. 200.txt
+    #[arg(long)]
+    pub split_output: Option<PathBuf>,
+
+    /// Group split output files by exact status `code` or status `class`, e.g. 2xx.txt
+    #[arg(long, default_value = "code")]
+    pub split_by: SplitBy,
+
+    /// Milliseconds between flushes of the split output files; 0 flushes every line
+    #[arg(long, value_name = "MS", requires = "split_output")]
+    pub flush_interval: Option<u64>,
+
+    /// Write the raw request and response of each hit to <dir>/<word>-<hash>.http, hashing its method and URL
+    #[arg(long, value_name = "DIR")]
+    pub capture_dir: Option<PathBuf>,
+
+    /// Periodically save how far each scan got to this file
+    #[arg(long, value_name = "FILE")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Continue from the checkpoint file, skipping words already scanned and keeping the totals so far
+    #[arg(long, requires = "checkpoint")]
+    pub resume: bool,
+
+    /// Print hits once the scan ends, collapsing clusters of similar responses into one line with a count
+    #[arg(long)]
+    pub cluster_output: bool,
+
+    /// Write the hits of the scan to this file as a single JSON document
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Indent the JSON written by --report
+    #[arg(long, requires = "report")]
+    pub output_json_pretty: bool,
+
+    /// Experimental: write the paths, methods and status codes of hits to this file as an OpenAPI 3 YAML skeleton
+    #[arg(long, value_name = "PATH")]
+    pub export_openapi: Option<PathBuf>,
+
+    /// Write the words of hits to this file, one per line, to use as the wordlist of a follow-up scan
+    #[arg(long, alias = "export-hits-as-wordlist", value_name = "PATH")]
+    pub hits_wordlist: Option<PathBuf>,
+
+    /// Stream to draw the progress bar on, stdout or stderr; hits are printed on the other one
+    #[arg(long, default_value = "stderr")]
+    pub progress_stream: ProgressStream,
+
+    /// Do not draw the progress bar; it is never drawn when its stream is not a terminal
+    #[arg(long)]
+    pub no_progress: bool,
+
+    /// Print nothing but hits: no progress bar, notes or summary
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Print hits as text or as JSON lines; with json, the progress bar and notes go to stderr
+    #[arg(long, default_value = "text")]
+    pub output_format: OutputFormat,
+
+    /// Also write hits to this file, in the output format; the progress bar stays on the terminal
     #[arg(short, long)]
-    pub verbose: bool,
+    pub output: Option<PathBuf>,
+
+    /// Append words that failed to probe, with the kind of error, to this file and keep scanning
+    #[arg(long)]
+    pub save_errors: Option<PathBuf>,
+
+    /// Probe each distinct URL only once and reuse its response for duplicates
+    #[arg(long)]
+    pub cache: bool,
+
+    /// Report words whose request times out, e.g. with --timeout-budget, as hits instead of skipping them
+    #[arg(long)]
+    pub timeout_as_hit: bool,
+
+    /// Stop probing a host, and report it as down, after this many requests to it failed in a row
+    #[arg(long, alias = "max-retries-per-host", value_name = "FAILURES")]
+    pub host_failure_threshold: Option<usize>,
+
+    /// Hide hits whose status and length keep coming back, once they make up over 2% of the requests, and say so
+    #[arg(long, alias = "filter-by-response-count-signature")]
+    pub auto_suppress_common: bool,
+
+    /// Print a curl command reproducing the request under each hit
+    #[arg(long)]
+    pub emit_curl: bool,
+
+    /// Show the first 80 characters of the response body next to each hit
+    #[arg(long)]
+    pub show_snippet: bool,
+
+    /// Show the detected character encoding of each hit, from its charset or byte order mark, in verbose output
+    #[arg(long)]
+    pub response_encoding_detection: bool,
+
+    /// Show the matched word next to the URL in non-verbose output
+    #[arg(long)]
+    pub show_word: bool,
+
+    /// Tune filters during the scan: type `s` to filter the size of the last hit, `c` its status code, `u` to undo, `p` to pause and `r` to resume
+    #[arg(long)]
+    pub interactive_filter: bool,
+}
+
+impl Cli {
+    /// The URLs to scan, one per port when ports are given.
+    pub fn targets(&self) -> Vec<Url> {
+        if self.ports.is_empty() {
+            return self.url.clone();
+        }
+
+        self.url.iter()
+            .flat_map(|url| self.ports.iter().filter_map(move |&port| {
+                let mut url = url.clone();
+                url.set_port(Some(port)).ok().map(|_| url)
+            }))
+            .collect()
+    }
+
+    pub fn filter_content_length(&self) -> crate::Result<FilterContentLength> {
+        self.parse_filter(&self.filter_content_length)
+    }
+
+    pub fn match_content_length(&self) -> crate::Result<FilterContentLength> {
+        self.parse_filter(&self.match_content_length)
+    }
+
+    pub fn filter_words(&self) -> crate::Result<FilterWordCount> {
+        self.parse_filter(&self.filter_words)
+    }
+
+    pub fn filter_lines(&self) -> crate::Result<FilterLineCount> {
+        self.parse_filter(&self.filter_lines)
+    }
+
+    /// The windows of `--filter-time-gt` and `--filter-time-lt`.
+    pub fn filter_response_time(&self) -> Vec<TimeRange> {
+        self.filter_time_gt.map(TimeRange::Above).into_iter()
+            .chain(self.filter_time_lt.map(TimeRange::Below))
+            .collect()
+    }
+
+    /// Parses strictly with `--strict-filters`, and otherwise skips the tokens that are not numbers.
+    fn parse_filter<T>(&self, value: &Option<String>) -> crate::Result<T>
+        where T: for<'a> From<&'a str> + FromStr<Err=crate::Error> {
+        match value {
+            None => "".parse(),
+            Some(value) if self.strict_filters => value.parse(),
+            Some(value) => Ok(T::from(value.as_str())),
+        }
+    }
 }
 
 fn parse_headers(s: &str) -> Result<(HeaderName, HeaderValue), Box<dyn Error + Send + Sync + 'static>> {
@@ -59,14 +568,53 @@ fn parse_headers(s: &str) -> Result<(HeaderName, HeaderValue), Box<dyn Error + S
     Ok((s[..pos].trim().parse()?, s[pos + 1..].trim().parse()?))
 }
 
+fn parse_seconds(s: &str) -> Result<f32, Box<dyn Error + Send + Sync + 'static>> {
+    match s.parse::<f32>()? {
+        seconds if seconds.is_finite() && seconds >= 0.0 => Ok(seconds),
+        _ => Err(format!("invalid seconds `{s}`: expected a finite number, 0 or more").into()),
+    }
+}
+
+fn parse_wordlist(s: &str) -> Result<(String, PathBuf), Box<dyn Error + Send + Sync + 'static>> {
+    let is_keyword = |k: &str| !k.is_empty() &&
+        k.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+    match s.split_once(':') {
+        Some((keyword, path)) if is_keyword(keyword) => Ok((keyword.to_string(), PathBuf::from(path))),
+        _ => Ok((FUZZ.to_string(), PathBuf::from(s))),
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
+    use std::path::PathBuf;
 
     use reqwest::header::{HeaderName, HeaderValue};
 
-    use crate::cli::parse_headers;
+    use clap::Parser;
+
+    use crate::cli::{Cli, parse_headers, parse_seconds, parse_wordlist};
+    use crate::filters::{FilterBody, FilterContentLength};
+
+    #[test]
+    fn strict_filters_fail_on_tokens_that_are_not_numbers() {
+        let args = |strict: &[&str]| Cli::parse_from(
+            [&["rustbuster", "-u", "http://127.0.0.1/FUZZ", "-w", "words.txt", "--filter-content-length", "20,2,ab"], strict].concat());
+
+        assert_eq!(args(&[]).filter_content_length().unwrap(), FilterContentLength::Separate(vec![20, 2]));
+        assert!(args(&["--strict-filters"]).filter_content_length().is_err());
+        assert_eq!(args(&["--strict-filters"]).filter_words().unwrap(), FilterContentLength::Empty.into());
+    }
+
+    #[test]
+    fn filter_body_rejects_an_invalid_regex() {
+        let args = |filter: &[&str]| Cli::try_parse_from(
+            [&["rustbuster", "-u", "http://127.0.0.1/FUZZ", "-w", "words.txt"], filter].concat());
+
+        assert_eq!(args(&[]).unwrap().filter_body, FilterBody::Empty);
+        assert!(args(&["--filter-body", "re:("]).is_err_and(|e| e.to_string().contains("invalid value 're:('")));
+    }
 
     #[test]
     fn parse_key_val_parses_colon() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
@@ -82,4 +630,33 @@ mod tests {
     fn parse_headers_invalid_header_name() {
         parse_headers("User Agent: hello").unwrap();
     }
+
+    #[test]
+    fn parse_wordlist_defaults_to_fuzz_keyword() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        assert_eq!(parse_wordlist("words.txt")?, ("FUZZ".to_string(), PathBuf::from("words.txt")));
+        assert_eq!(parse_wordlist("./a:b.txt")?, ("FUZZ".to_string(), PathBuf::from("./a:b.txt")));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_seconds_rejects_infinite_and_negative() {
+        assert_eq!(parse_seconds("0.5").unwrap(), 0.5);
+        for invalid in ["inf", "NaN", "-1", "soon"] {
+            assert!(parse_seconds(invalid).is_err(), "{invalid}");
+        }
+
+        let args = |seconds: &[&str]| Cli::try_parse_from(
+            [&["rustbuster", "-u", "http://127.0.0.1/FUZZ", "-w", "words.txt"], seconds].concat());
+        assert!(args(&["--timeout-budget", "1.5"]).is_ok_and(|a| a.timeout_budget == Some(1.5)));
+        assert!(args(&["--timeout-budget=-1"]).is_err());
+        assert!(args(&["--timeout-budget", "inf"]).is_err());
+        assert!(args(&["--ramp-up=-1"]).is_err());
+        assert!(args(&["--ramp-up", "inf"]).is_err());
+    }
+
+    #[test]
+    fn parse_wordlist_with_keyword() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        assert_eq!(parse_wordlist("H1:values.txt")?, ("H1".to_string(), PathBuf::from("values.txt")));
+        Ok(())
+    }
 }
@@ -11,6 +11,10 @@ pub enum Error {
 
     FuzzKeywordNotFound,
 
+    KeywordNotFound(String),
+
+    KeywordWithoutWordlist(String),
+
     #[from]
     Io(std::io::Error),
 
@@ -22,6 +26,44 @@ pub enum Error {
 
     #[from]
     HttpHeaderValueInvalid(reqwest::header::InvalidHeaderValue),
+
+    #[from]
+    Join(tokio::task::JoinError),
+
+    #[from]
+    Json(serde_json::Error),
+
+    /// A request got no response within the probe's timeout.
+    Timeout,
+}
+
+impl Error {
+    /// Coarse classification of why a probe failed.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Timeout => "timeout",
+            Error::Http(e) if e.is_timeout() => "timeout",
+            Error::Http(e) if e.is_connect() => "connection",
+            _ => "other",
+        }
+    }
+
+    /// Whether the request may succeed when sent again: it timed out, or its connection failed or was cut.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Timeout => true,
+            Error::Http(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            _ => false,
+        }
+    }
+
+    /// Tells timeouts apart from other transport errors.
+    pub fn from_http(e: reqwest::Error) -> Self {
+        match e.is_timeout() {
+            true => Error::Timeout,
+            false => Error::Http(e),
+        }
+    }
 }
 
 impl From<&str> for Error {
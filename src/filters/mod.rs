@@ -1,34 +1,273 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
 
+use regex::Regex;
 use reqwest::StatusCode;
+use reqwest::header::SET_COOKIE;
+use sha2::{Digest, Sha256};
 
+use crate::{Error, Result};
 use crate::probe::ProbeResponse;
 
 pub struct ProbeResponseFilters {
-    filter_status_codes: Vec<StatusCode>,
+    filter_status_codes: StatusCodeMatcher,
+    /// When not empty, only these status codes are kept, whatever `filter_status_codes` says.
+    match_status_codes: Vec<StatusCode>,
     filter_content_length: FilterContentLength,
+    /// When not Empty, only responses of these lengths are kept, unless `filter_content_length` ignores them.
+    match_content_length: FilterContentLength,
     filter_body: FilterBody,
+    filter_word_count: FilterWordCount,
+    filter_line_count: FilterLineCount,
+    added_content_lengths: Vec<u32>,
+    header_content_length_only: bool,
+    match_response_time: Option<TimeRange>,
+    filter_response_time: Vec<TimeRange>,
+    match_ttfb: Option<TimeRange>,
+    filter_ttfb: Option<TimeRange>,
+    filter_hashes: Vec<BodyHash>,
+    /// When set, only responses setting a cookie whose name contains it are kept; empty matches any cookie.
+    match_set_cookie: Option<String>,
+    match_mode: MatchMode,
+    normalize_trailing_whitespace: bool,
+    normalize_body_whitespace: bool,
+    not_found: Option<NotFound>,
+}
+
+/// A change to the active filters, applied while a scan is running.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterUpdate {
+    AddStatusCode(StatusCode),
+    RemoveStatusCode(StatusCode),
+    AddContentLength(u32),
+    RemoveContentLength(u32),
+}
+
+/// What the operator sees when an update is applied, e.g. `filtering size 35`; removals only come from undoing.
+impl Display for FilterUpdate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterUpdate::AddStatusCode(code) => write!(f, "filtering status {}", code.as_u16()),
+            FilterUpdate::RemoveStatusCode(code) => write!(f, "undo: filtering status {}", code.as_u16()),
+            FilterUpdate::AddContentLength(length) => write!(f, "filtering size {length}"),
+            FilterUpdate::RemoveContentLength(length) => write!(f, "undo: filtering size {length}"),
+        }
+    }
 }
 
 impl ProbeResponseFilters {
-    pub fn new(filter_status_codes: Vec<StatusCode>,
+    pub fn new(filter_status_codes: StatusCodeMatcher,
+               match_status_codes: Vec<StatusCode>,
                filter_content_length: FilterContentLength,
                filter_body: FilterBody) -> Self {
-        Self { filter_status_codes, filter_content_length, filter_body }
+        Self {
+            filter_status_codes,
+            match_status_codes,
+            filter_content_length,
+            match_content_length: FilterContentLength::Empty,
+            filter_body,
+            filter_word_count: FilterWordCount::Empty,
+            filter_line_count: FilterLineCount::Empty,
+            added_content_lengths: Vec::new(),
+            header_content_length_only: false,
+            match_response_time: None,
+            filter_response_time: Vec::new(),
+            match_ttfb: None,
+            filter_ttfb: None,
+            filter_hashes: Vec::new(),
+            match_set_cookie: None,
+            match_mode: MatchMode::All,
+            normalize_trailing_whitespace: false,
+            normalize_body_whitespace: false,
+            not_found: None,
+        }
+    }
+
+    /// Filter on the declared `Content-Length` header instead of the downloaded body size.
+    pub fn with_header_content_length_only(mut self, header_content_length_only: bool) -> Self {
+        self.header_content_length_only = header_content_length_only;
+        self
+    }
+
+    /// Only keep responses of these lengths.
+    pub fn with_match_content_length(mut self, match_content_length: FilterContentLength) -> Self {
+        self.match_content_length = match_content_length;
+        self
+    }
+
+    /// Ignore responses whose body has this many whitespace-separated words.
+    pub fn with_filter_word_count(mut self, filter_word_count: FilterWordCount) -> Self {
+        self.filter_word_count = filter_word_count;
+        self
+    }
+
+    /// Ignore responses whose body has this many lines.
+    pub fn with_filter_line_count(mut self, filter_line_count: FilterLineCount) -> Self {
+        self.filter_line_count = filter_line_count;
+        self
+    }
+
+    /// Only keep responses that took a time within `range`.
+    pub fn with_match_response_time(mut self, range: Option<TimeRange>) -> Self {
+        self.match_response_time = range;
+        self
+    }
+
+    /// Ignore responses whose time is in any of these windows, e.g. slower than 5s or faster than 10ms.
+    pub fn with_filter_response_time(mut self, ranges: Vec<TimeRange>) -> Self {
+        self.filter_response_time = ranges;
+        self
+    }
+
+    /// Only keep responses whose first byte arrived within `range`.
+    pub fn with_match_ttfb(mut self, range: Option<TimeRange>) -> Self {
+        self.match_ttfb = range;
+        self
+    }
+
+    /// Ignore responses whose first byte arrived within `range`.
+    pub fn with_filter_ttfb(mut self, range: Option<TimeRange>) -> Self {
+        self.filter_ttfb = range;
+        self
+    }
+
+    /// Ignore responses whose body has one of these SHA-256 hashes.
+    pub fn with_filter_hashes(mut self, hashes: Vec<BodyHash>) -> Self {
+        self.filter_hashes = hashes;
+        self
+    }
+
+    /// Only keep responses with a `Set-Cookie` header for a cookie whose name contains `name`, or any cookie when it is empty.
+    pub fn with_match_set_cookie(mut self, name: Option<String>) -> Self {
+        self.match_set_cookie = name;
+        self
+    }
+
+    /// Keep responses that satisfy all the configured matchers, or any one of them.
+    pub fn with_match_mode(mut self, match_mode: MatchMode) -> Self {
+        self.match_mode = match_mode;
+        self
+    }
+
+    /// Compare lengths and bodies without trailing whitespace, which servers add or omit inconsistently.
+    /// Responses still report their true size.
+    pub fn with_normalize_trailing_whitespace(mut self, normalize: bool) -> Self {
+        self.normalize_trailing_whitespace = normalize;
+        self
+    }
+
+    /// Match `--filter-body` against the body with every run of whitespace collapsed to one space.
+    /// Sizes and output still use the body as sent.
+    pub fn with_normalize_body_whitespace(mut self, normalize: bool) -> Self {
+        self.normalize_body_whitespace = normalize;
+        self
+    }
+
+    /// Ignore soft-404s: responses resembling `not_found`, whatever their status.
+    pub fn set_not_found(&mut self, not_found: NotFound) {
+        self.not_found = Some(not_found);
+    }
+
+    pub fn apply(&mut self, update: &FilterUpdate) {
+        match update {
+            FilterUpdate::AddStatusCode(code) => self.filter_status_codes.add(*code),
+            FilterUpdate::RemoveStatusCode(code) => self.filter_status_codes.remove(*code),
+            FilterUpdate::AddContentLength(length) => if !self.added_content_lengths.contains(length) {
+                self.added_content_lengths.push(*length)
+            },
+            FilterUpdate::RemoveContentLength(length) => self.added_content_lengths.retain(|l| l != length),
+        }
+    }
+
+    fn body_for_filter<'a>(&self, body: &'a str) -> Cow<'a, str> {
+        match self.normalize_body_whitespace {
+            true => Cow::Owned(body.split_whitespace().collect::<Vec<&str>>().join(" ")),
+            false => Cow::Borrowed(body),
+        }
     }
 
     pub fn filter(&self, response: ProbeResponse) -> Option<ProbeResponse> {
-        let ignore_response = self.filter_status_codes.contains(&response.status_code) ||
-            self.filter_content_length.matches(response.content_length) ||
-            self.filter_body.matches(&response.body);
+        let body = match self.normalize_trailing_whitespace {
+            true => response.body.trim_end(),
+            false => response.body.as_str(),
+        };
+        let content_length = match self.header_content_length_only {
+            true => response.header_content_length,
+            false if self.normalize_trailing_whitespace => Some(body.len() as u32),
+            false => Some(response.content_length),
+        };
 
-        return match ignore_response {
+        let ignore_status = self.match_status_codes.is_empty() && self.filter_status_codes.matches(response.status_code);
+
+        let matchers = [
+            (!self.match_status_codes.is_empty()).then(|| self.match_status_codes.contains(&response.status_code)),
+            (!self.match_content_length.is_empty()).then(|| content_length.is_some_and(|l| self.match_content_length.matches(l))),
+            self.match_response_time.map(|r| r.contains(response.response_time)),
+            self.match_ttfb.map(|r| r.contains(response.ttfb)),
+            self.match_set_cookie.as_ref().map(|name| sets_cookie(&response, name)),
+        ];
+
+        let ignore_response = ignore_status ||
+            !self.match_mode.combine(matchers.into_iter().flatten()) ||
+            content_length.is_some_and(|l| self.filter_content_length.matches(l)) ||
+            content_length.is_some_and(|l| self.added_content_lengths.contains(&l)) ||
+            self.filter_body.matches(&self.body_for_filter(body)) ||
+            self.filter_word_count.matches(body.split_whitespace().count() as u32) ||
+            self.filter_line_count.matches(body.lines().count() as u32) ||
+            self.filter_response_time.iter().any(|r| r.contains(response.response_time)) ||
+            self.filter_ttfb.is_some_and(|r| r.contains(response.ttfb)) ||
+            self.not_found.as_ref().is_some_and(|n| n.matches(&response)) ||
+            (!self.filter_hashes.is_empty() && self.filter_hashes.contains(&BodyHash::of(&response.body)));
+
+        match ignore_response {
             true => None,
             false => Some(response)
-        };
+        }
+    }
+}
+
+/// How the results of the configured matchers combine; with no matcher configured, every response matches.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MatchMode {
+    #[default]
+    All,
+    Any,
+}
+
+impl FromStr for MatchMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "all" => Ok(MatchMode::All),
+            "any" => Ok(MatchMode::Any),
+            other => Err(format!("invalid match mode `{other}`: expected all or any").into()),
+        }
     }
 }
 
+impl MatchMode {
+    fn combine(self, matchers: impl Iterator<Item=bool>) -> bool {
+        let mut matchers = matchers.peekable();
+        matchers.peek().is_none() || match self {
+            MatchMode::All => matchers.all(|m| m),
+            MatchMode::Any => matchers.any(|m| m),
+        }
+    }
+}
+
+/// Whether `response` sets a cookie whose name contains `name`.
+fn sets_cookie(response: &ProbeResponse, name: &str) -> bool {
+    response.headers.get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|cookie| cookie.split('=').next().is_some_and(|n| n.trim().contains(name)))
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum FilterContentLength {
     Separate(Vec<u32>),
@@ -46,18 +285,40 @@ impl From<&str> for FilterContentLength {
     fn from(value: &str) -> Self {
         if value.contains("-") {
             return Self::from_nums(value.split("-")
-                .map(|v| v.parse::<u32>())
-                .flatten()
+                .flat_map(|v| v.parse::<u32>())
                 .collect());
         }
 
         FilterContentLength::Separate(value.split(",")
-            .map(|v| v.parse::<u32>())
-            .flatten()
+            .flat_map(|v| v.parse::<u32>())
             .collect())
     }
 }
 
+/// Unlike `From<&str>`, fails on the tokens it would skip, listing them.
+impl FromStr for FilterContentLength {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (tokens, range) = match s.contains('-') {
+            true => (s.split('-').collect::<Vec<&str>>(), true),
+            false => (s.split(',').filter(|t| !t.is_empty()).collect(), false),
+        };
+
+        let invalid: Vec<&str> = tokens.iter().filter(|t| t.parse::<u32>().is_err()).copied().collect();
+        if !invalid.is_empty() {
+            return Err(format!("invalid filter `{s}`: expected numbers, not `{}`", invalid.join("`, `")).into());
+        }
+
+        match tokens.iter().map(|t| t.parse::<u32>().unwrap()).collect::<Vec<u32>>()[..] {
+            [a, b] if range && a < b => Ok(FilterContentLength::Range(a, b)),
+            _ if range => Err(format!("invalid filter `{s}`: expected a range MIN-MAX with MIN below MAX").into()),
+            [] => Ok(FilterContentLength::Empty),
+            ref lengths => Ok(FilterContentLength::Separate(lengths.to_vec())),
+        }
+    }
+}
+
 impl FilterContentLength {
     pub fn matches(&self, length: u32) -> bool {
         match self {
@@ -67,6 +328,15 @@ impl FilterContentLength {
         }
     }
 
+    /// Whether no length is given, as when lenient parsing skips every token.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            FilterContentLength::Empty => true,
+            FilterContentLength::Separate(v) => v.is_empty(),
+            FilterContentLength::Range(..) => false,
+        }
+    }
+
     fn from_nums(nums: Vec<u32>) -> FilterContentLength {
         let nums: [u32; 2] = nums.try_into()
             .unwrap_or_else(|_| panic!("expected 2 values in content length range"));
@@ -80,16 +350,305 @@ impl FilterContentLength {
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum FilterBody {
-    Text(String),
+pub enum FilterWordCount {
+    Separate(Vec<u32>),
+    Range(u32, u32),
     Empty,
 }
 
-impl From<&str> for FilterBody {
+impl Display for FilterWordCount {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<FilterContentLength> for FilterWordCount {
+    fn from(value: FilterContentLength) -> Self {
+        match value {
+            FilterContentLength::Separate(v) => FilterWordCount::Separate(v),
+            FilterContentLength::Range(a, b) => FilterWordCount::Range(a, b),
+            FilterContentLength::Empty => FilterWordCount::Empty,
+        }
+    }
+}
+
+/// Parsed like content lengths, e.g. 20,300 or 20-300.
+impl From<&str> for FilterWordCount {
     fn from(value: &str) -> Self {
+        FilterContentLength::from(value).into()
+    }
+}
+
+impl FromStr for FilterWordCount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.parse::<FilterContentLength>().map(|l| l.into())
+    }
+}
+
+impl FilterWordCount {
+    pub fn matches(&self, words: u32) -> bool {
+        match self {
+            FilterWordCount::Empty => false,
+            FilterWordCount::Separate(v) => v.contains(&words),
+            FilterWordCount::Range(a, b) => a <= &words && &words <= b
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterLineCount {
+    Separate(Vec<u32>),
+    Range(u32, u32),
+    Empty,
+}
+
+impl Display for FilterLineCount {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<FilterContentLength> for FilterLineCount {
+    fn from(value: FilterContentLength) -> Self {
         match value {
-            "" => FilterBody::Empty,
-            v => FilterBody::Text(String::from(v))
+            FilterContentLength::Separate(v) => FilterLineCount::Separate(v),
+            FilterContentLength::Range(a, b) => FilterLineCount::Range(a, b),
+            FilterContentLength::Empty => FilterLineCount::Empty,
+        }
+    }
+}
+
+/// Parsed like content lengths, e.g. 20,300 or 20-300.
+impl From<&str> for FilterLineCount {
+    fn from(value: &str) -> Self {
+        FilterContentLength::from(value).into()
+    }
+}
+
+impl FromStr for FilterLineCount {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.parse::<FilterContentLength>().map(|l| l.into())
+    }
+}
+
+impl FilterLineCount {
+    pub fn matches(&self, lines: u32) -> bool {
+        match self {
+            FilterLineCount::Empty => false,
+            FilterLineCount::Separate(v) => v.contains(&lines),
+            FilterLineCount::Range(a, b) => a <= &lines && &lines <= b
+        }
+    }
+}
+
+/// Status codes given as single codes (`404`), ranges (`500-599`) or classes (`4xx`), e.g. `401,403,5xx`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatusCodeMatcher {
+    ranges: Vec<RangeInclusive<u16>>,
+}
+
+impl FromStr for StatusCodeMatcher {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |entry: &str| Error::from(format!("invalid status code `{entry}`: expected CODE, CODE-CODE or Nxx"));
+        let code = |v: &str, entry: &str| v.trim().parse::<StatusCode>().map(|c| c.as_u16()).map_err(|_| invalid(entry));
+
+        let ranges = s.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match (entry.strip_suffix("xx"), entry.split_once('-')) {
+                (Some(class), _) if class.len() == 1 => {
+                    let start = code(&format!("{class}00"), entry)?;
+                    Ok(start..=start + 99)
+                }
+                (_, Some((a, b))) => match (code(a, entry)?, code(b, entry)?) {
+                    (a, b) if a <= b => Ok(a..=b),
+                    _ => Err(invalid(entry)),
+                },
+                _ => code(entry, entry).map(|c| c..=c),
+            })
+            .collect::<Result<Vec<RangeInclusive<u16>>>>()?;
+
+        Ok(StatusCodeMatcher { ranges })
+    }
+}
+
+impl From<Vec<StatusCode>> for StatusCodeMatcher {
+    fn from(codes: Vec<StatusCode>) -> Self {
+        StatusCodeMatcher { ranges: codes.iter().map(|c| c.as_u16()..=c.as_u16()).collect() }
+    }
+}
+
+impl StatusCodeMatcher {
+    pub fn matches(&self, code: StatusCode) -> bool {
+        self.ranges.iter().any(|r| r.contains(&code.as_u16()))
+    }
+
+    pub fn add(&mut self, code: StatusCode) {
+        if !self.matches(code) {
+            self.ranges.push(code.as_u16()..=code.as_u16());
+        }
+    }
+
+    /// Stops matching `code`, splitting any range around it.
+    pub fn remove(&mut self, code: StatusCode) {
+        let code = code.as_u16();
+        self.ranges = self.ranges.iter()
+            .flat_map(|r| match r.contains(&code) {
+                true => vec![*r.start()..=code - 1, code + 1..=*r.end()],
+                false => vec![r.clone()],
+            })
+            .filter(|r| !r.is_empty())
+            .collect();
+    }
+}
+
+/// A window of response times in milliseconds: above (`>5000`), below (`<100`) or within (`1000-2000`) bounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeRange {
+    Above(u64),
+    Below(u64),
+    Within(u64, u64),
+}
+
+impl FromStr for TimeRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::from(format!("invalid time range `{s}`: expected >MS, <MS or MS-MS"));
+        let ms = |v: &str| v.trim().parse::<u64>().map_err(|_| invalid());
+
+        if let Some(v) = s.strip_prefix('>') {
+            return Ok(TimeRange::Above(ms(v)?));
+        }
+        if let Some(v) = s.strip_prefix('<') {
+            return Ok(TimeRange::Below(ms(v)?));
+        }
+
+        match s.split_once('-') {
+            Some((a, b)) if ms(a)? <= ms(b)? => Ok(TimeRange::Within(ms(a)?, ms(b)?)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TimeRange {
+    pub fn contains(&self, time: Duration) -> bool {
+        let ms = time.as_millis() as u64;
+
+        match *self {
+            TimeRange::Above(min) => ms > min,
+            TimeRange::Below(max) => ms < max,
+            TimeRange::Within(min, max) => min <= ms && ms <= max,
+        }
+    }
+}
+
+/// Body similarity from which a response resembles the not found page.
+const SIMILAR_BODY: f64 = 0.8;
+
+/// The response to a word that cannot exist, which soft-404 pages resemble even when served as 200.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NotFound {
+    status_code: StatusCode,
+    content_length: u32,
+    title: String,
+    tokens: HashSet<String>,
+}
+
+impl NotFound {
+    pub fn of(response: &ProbeResponse) -> Self {
+        Self {
+            status_code: response.status_code,
+            content_length: response.content_length,
+            title: response.title(),
+            tokens: tokens(&response.body),
+        }
+    }
+
+    /// Whether `response` is a success or has the same status, is about as long, and has a similar body or the same title.
+    pub fn matches(&self, response: &ProbeResponse) -> bool {
+        let status = response.status_code == self.status_code || response.status_code.is_success();
+        let length = self.content_length.abs_diff(response.content_length) <= (self.content_length / 10).max(16);
+        let title = !self.title.is_empty() && self.title == response.title();
+
+        status && length && (title || similarity(&self.tokens, &tokens(&response.body)) >= SIMILAR_BODY)
+    }
+}
+
+/// The lowercase words of a body.
+pub fn tokens(body: &str) -> HashSet<String> {
+    body.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+/// The share of words two bodies have in common, from 0 to 1 for identical ones.
+pub fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    match a.union(b).count() {
+        0 => 1.0,
+        union => a.intersection(b).count() as f64 / union as f64,
+    }
+}
+
+/// The hex-encoded SHA-256 hash of a response body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodyHash(String);
+
+impl FromStr for BodyHash {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => Ok(BodyHash(s.to_ascii_lowercase())),
+            false => Err(format!("invalid body hash `{s}`: expected a hex-encoded SHA-256 digest").into()),
+        }
+    }
+}
+
+impl BodyHash {
+    pub fn of(body: &str) -> Self {
+        BodyHash(Sha256::digest(body.as_bytes()).iter().map(|b| format!("{b:02x}")).collect())
+    }
+}
+
+/// Text the body contains, or a regex given as `re:PATTERN` that matches somewhere in it.
+#[derive(Clone, Debug)]
+pub enum FilterBody {
+    Text(String),
+    Regex(Regex),
+    Empty,
+}
+
+impl PartialEq for FilterBody {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FilterBody::Text(a), FilterBody::Text(b)) => a == b,
+            (FilterBody::Regex(a), FilterBody::Regex(b)) => a.as_str() == b.as_str(),
+            (FilterBody::Empty, FilterBody::Empty) => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for FilterBody {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "" => Ok(FilterBody::Empty),
+            v => match v.strip_prefix("re:") {
+                Some(pattern) => Regex::new(pattern)
+                    .map(FilterBody::Regex)
+                    .map_err(|e| format!("invalid body regex `{pattern}`: {e}").into()),
+                None => Ok(FilterBody::Text(String::from(v))),
+            }
         }
     }
 }
@@ -99,6 +658,7 @@ impl FilterBody {
         match self {
             FilterBody::Empty => false,
             FilterBody::Text(c) => content.contains(c),
+            FilterBody::Regex(r) => r.is_match(content),
         }
     }
 }
@@ -111,15 +671,18 @@ impl Display for FilterBody {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use reqwest::StatusCode;
 
-    use crate::filters::{FilterBody, FilterContentLength, ProbeResponseFilters};
+    use crate::filters::{BodyHash, FilterBody, FilterContentLength, FilterLineCount, FilterUpdate, FilterWordCount, MatchMode, NotFound, ProbeResponseFilters, StatusCodeMatcher, TimeRange};
     use crate::probe::ProbeResponse;
 
     #[test]
     fn filter_none_matches_returns_response() -> Result<(), String> {
         let filters = ProbeResponseFilters::new(
-            vec![StatusCode::NOT_FOUND],
+            StatusCodeMatcher::from(vec![StatusCode::NOT_FOUND]),
+            Vec::new(),
             FilterContentLength::Empty,
             FilterBody::Empty,
         );
@@ -129,6 +692,7 @@ mod tests {
             status_code: StatusCode::OK,
             content_length: 50,
             body: "".to_string(),
+            ..Default::default()
         };
 
         match filters.filter(response) {
@@ -144,7 +708,8 @@ mod tests {
     #[test]
     fn filter_ignores_status_codes() {
         let filters = ProbeResponseFilters::new(
-            vec![StatusCode::NOT_FOUND],
+            StatusCodeMatcher::from(vec![StatusCode::NOT_FOUND]),
+            Vec::new(),
             FilterContentLength::Empty,
             FilterBody::Empty,
         );
@@ -154,14 +719,92 @@ mod tests {
             status_code: StatusCode::NOT_FOUND,
             content_length: 50,
             body: "".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(filters.filter(response), None);
     }
 
+    #[test]
+    fn status_code_matcher_parses_ranges_and_classes() {
+        let matcher = "404, 500-599,4xx,550-560".parse::<StatusCodeMatcher>().unwrap();
+
+        assert!(matcher.matches(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(matcher.matches(StatusCode::NOT_FOUND));
+        assert!(matcher.matches(StatusCode::from_u16(499).unwrap()));
+        assert!(!matcher.matches(StatusCode::OK));
+
+        let matcher = "500-599".parse::<StatusCodeMatcher>().unwrap();
+        assert!(matcher.matches(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!matcher.matches(StatusCode::from_u16(499).unwrap()));
+        assert!(!matcher.matches(StatusCode::from_u16(600).unwrap()));
+
+        assert!("599-500".parse::<StatusCodeMatcher>().is_err());
+        assert!("6xx".parse::<StatusCodeMatcher>().is_ok());
+        assert!("0xx".parse::<StatusCodeMatcher>().is_err());
+        assert!("40x".parse::<StatusCodeMatcher>().is_err());
+        assert!("abc".parse::<StatusCodeMatcher>().is_err());
+    }
+
+    #[test]
+    fn removing_a_status_code_splits_its_range() {
+        let mut matcher = "4xx,400-410".parse::<StatusCodeMatcher>().unwrap();
+        matcher.remove(StatusCode::NOT_FOUND);
+
+        assert!(!matcher.matches(StatusCode::NOT_FOUND));
+        assert!(matcher.matches(StatusCode::FORBIDDEN));
+        assert!(matcher.matches(StatusCode::METHOD_NOT_ALLOWED));
+        assert!(matcher.matches(StatusCode::from_u16(499).unwrap()));
+
+        matcher.add(StatusCode::NOT_FOUND);
+        assert!(matcher.matches(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn filter_match_status_codes_keeps_only_those() {
+        let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::from(vec![StatusCode::OK]),
+            vec![StatusCode::OK],
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        );
+
+        let response = |status_code: StatusCode| ProbeResponse {
+            status_code,
+            ..Default::default()
+        };
+
+        assert!(filters.filter(response(StatusCode::OK)).is_some());
+        assert_eq!(filters.filter(response(StatusCode::INTERNAL_SERVER_ERROR)), None);
+    }
+
+    #[test]
+    fn match_mode_combines_matchers() {
+        let filters = |match_mode: MatchMode| ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            vec![StatusCode::OK],
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        ).with_match_content_length(FilterContentLength::from("35")).with_match_mode(match_mode);
+
+        let response = |status_code: StatusCode, content_length: u32| ProbeResponse {
+            status_code,
+            content_length,
+            ..Default::default()
+        };
+
+        assert!(filters(MatchMode::All).filter(response(StatusCode::OK, 35)).is_some());
+        assert_eq!(filters(MatchMode::All).filter(response(StatusCode::OK, 10)), None);
+        assert!(filters(MatchMode::Any).filter(response(StatusCode::OK, 10)).is_some());
+        assert!(filters(MatchMode::Any).filter(response(StatusCode::FORBIDDEN, 35)).is_some());
+        assert_eq!(filters(MatchMode::Any).filter(response(StatusCode::FORBIDDEN, 10)), None);
+        assert!("either".parse::<MatchMode>().is_err());
+    }
+
     #[test]
     fn filter_ignores_content_length() {
         let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
             Vec::new(),
             FilterContentLength::Separate(vec![35]),
             FilterBody::Empty,
@@ -172,14 +815,57 @@ mod tests {
             status_code: StatusCode::NOT_FOUND,
             content_length: 35,
             body: "".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(filters.filter(response), None);
     }
 
+    #[test]
+    fn match_content_length_keeps_only_matching_lengths() {
+        let response = |content_length: u32| ProbeResponse {
+            status_code: StatusCode::OK,
+            content_length,
+            ..Default::default()
+        };
+        let filters = |filter_content_length: FilterContentLength| ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            filter_content_length,
+            FilterBody::Empty,
+        ).with_match_content_length(FilterContentLength::from("35"));
+
+        let matching = filters(FilterContentLength::Empty);
+        assert!(matching.filter(response(35)).is_some());
+        assert_eq!(matching.filter(response(10)), None);
+
+        let unset = filters(FilterContentLength::Empty).with_match_content_length(FilterContentLength::from("Empty"));
+        assert!(unset.filter(response(10)).is_some());
+
+        let combined = filters(FilterContentLength::from("30-40"));
+        assert_eq!(combined.filter(response(35)), None);
+    }
+
+    #[test]
+    fn strict_content_length_lists_invalid_tokens() {
+        assert_eq!(FilterContentLength::from("20,2,ab"), FilterContentLength::Separate(vec![20, 2]));
+        match "20,2,ab,x".parse::<FilterContentLength>() {
+            Err(crate::Error::Custom(e)) => assert_eq!(e, "invalid filter `20,2,ab,x`: expected numbers, not `ab`, `x`"),
+            r => panic!("expected invalid tokens, got {r:?}"),
+        }
+
+        assert_eq!("20,2".parse::<FilterContentLength>().unwrap(), FilterContentLength::Separate(vec![20, 2]));
+        assert_eq!("20-300".parse::<FilterContentLength>().unwrap(), FilterContentLength::Range(20, 300));
+        assert_eq!("".parse::<FilterContentLength>().unwrap(), FilterContentLength::Empty);
+        assert!("300-20".parse::<FilterContentLength>().is_err());
+        assert!("20-ab".parse::<FilterContentLength>().is_err());
+        assert!("20-30-40".parse::<FilterContentLength>().is_err());
+    }
+
     #[test]
     fn filter_body_contains_is_ignored() {
         let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
             Vec::new(),
             FilterContentLength::Separate(vec![35]),
             FilterBody::Text("strange word!".to_string()),
@@ -190,11 +876,253 @@ mod tests {
             status_code: StatusCode::NOT_FOUND,
             content_length: 35,
             body: "this contains a strange word!".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(filters.filter(response), None);
     }
 
+    #[test]
+    fn filter_body_regex_is_ignored() {
+        let filter_body: FilterBody = "re:^Not Found.*".parse().unwrap();
+        let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            filter_body.clone(),
+        );
+
+        let response = |body: &str| ProbeResponse {
+            status_code: StatusCode::OK,
+            body: body.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(filter_body, "re:^Not Found.*".parse().unwrap());
+        assert_ne!(filter_body, "^Not Found.*".parse().unwrap());
+        assert!("re:(".parse::<FilterBody>().is_err());
+        assert_eq!(filters.filter(response("Not Found: /admin-4f2a")), None);
+        assert!(filters.filter(response("Page Not Found")).is_some());
+    }
+
+    #[test]
+    fn filter_word_count_ignores_separate_and_range() {
+        let response = |body: &str| ProbeResponse {
+            status_code: StatusCode::OK,
+            body: body.to_string(),
+            ..Default::default()
+        };
+        let filters = |filter_word_count: FilterWordCount| ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        ).with_filter_word_count(filter_word_count);
+
+        let separate = filters(FilterWordCount::from("3,5"));
+        assert_eq!(separate.filter(response("not  found\nhere")), None);
+        assert!(separate.filter(response("not found")).is_some());
+
+        let range = filters(FilterWordCount::from("2-4"));
+        assert_eq!(range.filter(response("not found")), None);
+        assert_eq!(range.filter(response("page is not found")), None);
+        assert!(range.filter(response("this page is not found")).is_some());
+    }
+
+    #[test]
+    fn filter_line_count_ignores_separate_and_range() {
+        let response = |body: &str| ProbeResponse {
+            status_code: StatusCode::OK,
+            body: body.to_string(),
+            ..Default::default()
+        };
+        let filters = |filter_line_count: FilterLineCount| ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        ).with_filter_line_count(filter_line_count);
+
+        let separate = filters(FilterLineCount::from("1,3"));
+        assert_eq!(separate.filter(response("a\nb\nc\n")), None);
+        assert!(separate.filter(response("a\nb")).is_some());
+
+        let range = filters(FilterLineCount::from("2-3"));
+        assert_eq!(range.filter(response("a\nb")), None);
+        assert!(range.filter(response("a")).is_some());
+        assert!(range.filter(response("a\nb\nc\nd")).is_some());
+    }
+
+    #[test]
+    fn filter_updates_change_ignored_responses() {
+        let mut filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        );
+
+        let response = || ProbeResponse {
+            request_url: "url".to_string(),
+            status_code: StatusCode::OK,
+            content_length: 35,
+            body: "".to_string(),
+            ..Default::default()
+        };
+
+        filters.apply(&FilterUpdate::AddContentLength(35));
+        assert_eq!(filters.filter(response()), None);
+
+        filters.apply(&FilterUpdate::RemoveContentLength(35));
+        filters.apply(&FilterUpdate::AddStatusCode(StatusCode::OK));
+        assert_eq!(filters.filter(response()), None);
+
+        filters.apply(&FilterUpdate::RemoveStatusCode(StatusCode::OK));
+        assert!(filters.filter(response()).is_some());
+    }
+
+    #[test]
+    fn filter_updates_read_as_messages() {
+        assert_eq!(FilterUpdate::AddContentLength(35).to_string(), "filtering size 35");
+        assert_eq!(FilterUpdate::RemoveContentLength(35).to_string(), "undo: filtering size 35");
+        assert_eq!(FilterUpdate::AddStatusCode(StatusCode::FORBIDDEN).to_string(), "filtering status 403");
+        assert_eq!(FilterUpdate::RemoveStatusCode(StatusCode::FORBIDDEN).to_string(), "undo: filtering status 403");
+    }
+
+    #[test]
+    fn filter_header_content_length_only_ignores_body_size() {
+        let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Separate(vec![100]),
+            FilterBody::Empty,
+        ).with_header_content_length_only(true);
+
+        let declared = ProbeResponse {
+            content_length: 0,
+            header_content_length: Some(100),
+            ..Default::default()
+        };
+        let undeclared = ProbeResponse {
+            content_length: 100,
+            header_content_length: None,
+            ..Default::default()
+        };
+
+        assert_eq!(filters.filter(declared), None);
+        assert!(filters.filter(undeclared).is_some());
+    }
+
+    #[test]
+    fn filter_ignores_body_hash() {
+        let hash = "0019DFC4B32D63C1392AA264AED2253C1E0C2FB09216F8E2CC269BBFB8BB49B5".parse::<BodyHash>().unwrap();
+        assert_eq!(hash, BodyHash::of("Not Found"));
+        assert!("abc".parse::<BodyHash>().is_err());
+
+        let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        ).with_filter_hashes(vec![hash]);
+
+        let response = |body: &str| ProbeResponse {
+            body: body.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(filters.filter(response("Not Found")), None);
+        assert!(filters.filter(response("Welcome")).is_some());
+    }
+
+    #[test]
+    fn filter_normalizes_trailing_whitespace() {
+        let filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Separate(vec![9]),
+            FilterBody::Empty,
+        ).with_normalize_trailing_whitespace(true);
+
+        let response = |body: &str| ProbeResponse {
+            content_length: body.len() as u32,
+            body: body.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(filters.filter(response("Not Found")), None);
+        assert_eq!(filters.filter(response("Not Found\n")), None);
+        assert_eq!(filters.filter(response("Not Found!\n")).map(|r| r.content_length), Some(11));
+    }
+
+    #[test]
+    fn filter_body_ignores_whitespace_differences_when_normalized() {
+        let filters = |normalize: bool| ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            "<p>Page not found</p>".parse().unwrap(),
+        ).with_normalize_body_whitespace(normalize);
+
+        let response = |body: &str| ProbeResponse {
+            content_length: body.len() as u32,
+            body: body.to_string(),
+            ..Default::default()
+        };
+        let spaced = "\n  <p>Page\n\tnot   found</p>\n";
+
+        assert_eq!(filters(true).filter(response("<p>Page not found</p>")), None);
+        assert_eq!(filters(true).filter(response(spaced)), None);
+        assert_eq!(filters(false).filter(response(spaced)).map(|r| r.body), Some(spaced.to_string()));
+        assert!(filters(true).filter(response("<p>Welcome</p>")).is_some());
+    }
+
+    #[test]
+    fn filter_ignores_soft_404() {
+        let mut filters = ProbeResponseFilters::new(
+            StatusCodeMatcher::from(vec![StatusCode::NOT_FOUND]),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        );
+
+        let response = |status_code: StatusCode, body: String| ProbeResponse {
+            status_code,
+            content_length: body.len() as u32,
+            body,
+            ..Default::default()
+        };
+        let page = |title: &str, text: &str| format!("<html><title>{title}</title><p>{text}</p></html>");
+
+        filters.set_not_found(NotFound::of(&response(StatusCode::NOT_FOUND,
+                                                     page("Oops", "The page 8f14e45fceea167a could not be found"))));
+
+        assert_eq!(filters.filter(response(StatusCode::OK, page("Oops", "The page backup could not be found"))), None);
+        assert!(filters.filter(response(StatusCode::OK, page("Admin", "Welcome to the administration console"))).is_some());
+        assert!(filters.filter(response(StatusCode::MOVED_PERMANENTLY, String::new())).is_some());
+    }
+
+    #[test]
+    fn filter_and_match_ttfb() {
+        let filters = |match_ttfb: Option<TimeRange>, filter_ttfb: Option<TimeRange>| ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        ).with_match_ttfb(match_ttfb).with_filter_ttfb(filter_ttfb);
+
+        let response = |ms: u64| ProbeResponse {
+            ttfb: Duration::from_millis(ms),
+            response_time: Duration::from_millis(ms + 1000),
+            ..Default::default()
+        };
+
+        assert_eq!(filters(Some(TimeRange::Above(500)), None).filter(response(100)), None);
+        assert!(filters(Some(TimeRange::Above(500)), None).filter(response(900)).is_some());
+        assert_eq!(filters(None, Some(TimeRange::Below(200))).filter(response(100)), None);
+        assert!(filters(None, Some(TimeRange::Below(200))).filter(response(900)).is_some());
+    }
+
     #[test]
     fn exclude_lengths_from_str_separate() {
         let exclude_lengths = FilterContentLength::from("30,12");
@@ -235,4 +1163,17 @@ mod tests {
         assert!(!FilterContentLength::Range(200, 404).matches(500));
         assert!(FilterContentLength::Range(200, 500).matches(500));
     }
+
+    #[test]
+    fn parse_time_ranges() {
+        assert_eq!(">5000".parse::<TimeRange>().unwrap(), TimeRange::Above(5000));
+        assert_eq!("<100".parse::<TimeRange>().unwrap(), TimeRange::Below(100));
+        assert_eq!("1000-2000".parse::<TimeRange>().unwrap(), TimeRange::Within(1000, 2000));
+        assert!("2000-1000".parse::<TimeRange>().is_err());
+        assert!("slow".parse::<TimeRange>().is_err());
+
+        assert!(TimeRange::Above(5000).contains(Duration::from_millis(5001)));
+        assert!(!TimeRange::Above(5000).contains(Duration::from_millis(5000)));
+        assert!(TimeRange::Within(1000, 2000).contains(Duration::from_secs(2)));
+    }
 }
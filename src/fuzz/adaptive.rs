@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const WINDOW: usize = 20;
+const WITHHOLD_AT: f32 = 0.25;
+const RESTORE_AT: f32 = 0.05;
+
+#[derive(Debug, PartialEq)]
+pub enum Adjustment {
+    Withhold,
+    Restore,
+    Keep,
+}
+
+/// Tracks the rate of unhealthy responses (5xx or failed requests) over a rolling window,
+/// and decides whether a finished request's permit should be withheld or an earlier one restored.
+pub struct AdaptiveConcurrency {
+    state: Mutex<State>,
+}
+
+struct State {
+    outcomes: VecDeque<bool>,
+    withheld: usize,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new() -> Self {
+        Self { state: Mutex::new(State { outcomes: VecDeque::with_capacity(WINDOW), withheld: 0 }) }
+    }
+
+    pub fn record(&self, healthy: bool) {
+        let mut state = self.state.lock().expect("adaptive concurrency lock poisoned");
+        if state.outcomes.len() == WINDOW {
+            state.outcomes.pop_front();
+        }
+        state.outcomes.push_back(healthy);
+    }
+
+    /// Never withholds so many permits that fewer than one request can run.
+    pub fn adjust(&self, threads: usize) -> Adjustment {
+        let mut state = self.state.lock().expect("adaptive concurrency lock poisoned");
+        if state.outcomes.is_empty() {
+            return Adjustment::Keep;
+        }
+
+        let unhealthy = state.outcomes.iter().filter(|&&healthy| !healthy).count();
+        let error_rate = unhealthy as f32 / state.outcomes.len() as f32;
+
+        if error_rate >= WITHHOLD_AT && state.withheld + 1 < threads {
+            state.withheld += 1;
+            Adjustment::Withhold
+        } else if error_rate <= RESTORE_AT && state.withheld > 0 {
+            state.withheld -= 1;
+            Adjustment::Restore
+        } else {
+            Adjustment::Keep
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::adaptive::{Adjustment, AdaptiveConcurrency};
+
+    #[test]
+    fn withholds_on_errors_and_restores_when_healthy() {
+        let adaptive = AdaptiveConcurrency::new();
+
+        (0..10).for_each(|_| adaptive.record(false));
+        assert_eq!(adaptive.adjust(3), Adjustment::Withhold);
+        assert_eq!(adaptive.adjust(3), Adjustment::Withhold);
+        assert_eq!(adaptive.adjust(3), Adjustment::Keep);
+
+        (0..20).for_each(|_| adaptive.record(true));
+        assert_eq!(adaptive.adjust(3), Adjustment::Restore);
+        assert_eq!(adaptive.adjust(3), Adjustment::Restore);
+        assert_eq!(adaptive.adjust(3), Adjustment::Keep);
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Counts consecutive failed requests per host, and takes a host out of the scan once it reaches the threshold.
+pub struct HostBreaker {
+    threshold: usize,
+    failures: Mutex<HashMap<String, usize>>,
+}
+
+impl HostBreaker {
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold: threshold.max(1), failures: Mutex::new(HashMap::new()) }
+    }
+
+    /// Whether `host` failed too often to probe it any further.
+    pub fn is_tripped(&self, host: &str) -> bool {
+        self.failures.lock().expect("host failures lock poisoned")
+            .get(host)
+            .is_some_and(|&failures| failures >= self.threshold)
+    }
+
+    /// Returns whether this failure is the one that trips `host`. A success resets the count
+    /// of a host that has not been tripped yet.
+    pub fn record(&self, host: &str, failed: bool) -> bool {
+        let mut failures = self.failures.lock().expect("host failures lock poisoned");
+        let count = failures.entry(host.to_string()).or_default();
+        match (failed, *count >= self.threshold) {
+            (_, true) => false,
+            (true, false) => {
+                *count += 1;
+                *count == self.threshold
+            }
+            (false, false) => {
+                *count = 0;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::breaker::HostBreaker;
+
+    #[test]
+    fn trips_after_consecutive_failures_only() {
+        let breaker = HostBreaker::new(2);
+
+        assert!(!breaker.record("a.test", true));
+        assert!(!breaker.record("a.test", false));
+        assert!(!breaker.record("a.test", true));
+        assert!(!breaker.is_tripped("a.test"));
+
+        assert!(breaker.record("a.test", true));
+        assert!(breaker.is_tripped("a.test"));
+        assert!(!breaker.record("a.test", false));
+        assert!(breaker.is_tripped("a.test"));
+        assert!(!breaker.is_tripped("b.test"));
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+
+use crate::probe::ProbeResponse;
+
+/// The status code and content length that more than half of a sample of words got back,
+/// most likely the target's answer for paths that do not exist.
+pub struct Suggestion {
+    pub response: ProbeResponse,
+    count: usize,
+    sampled: usize,
+}
+
+impl Suggestion {
+    pub fn content_length(&self) -> u32 {
+        self.response.content_length
+    }
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Suggested filter: --filter-content-length {} ({} of {} sampled words returned {} with {} bytes)",
+               self.response.content_length, self.count, self.sampled, self.response.status_code.as_u16(), self.response.content_length)
+    }
+}
+
+/// The dominant signature among `responses`, if more than half of them share one.
+pub fn suggest(responses: Vec<ProbeResponse>) -> Option<Suggestion> {
+    let sampled = responses.len();
+    let mut signatures: Vec<((StatusCode, u32), ProbeResponse, usize)> = Vec::new();
+    for response in responses {
+        let key = (response.status_code, response.content_length);
+        match signatures.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, count)) => *count += 1,
+            None => signatures.push((key, response, 1)),
+        }
+    }
+
+    signatures.into_iter()
+        .max_by_key(|(_, _, count)| *count)
+        .filter(|(_, _, count)| count * 2 > sampled)
+        .map(|(_, response, count)| Suggestion { response, count, sampled })
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use crate::fuzz::calibrate::suggest;
+    use crate::probe::ProbeResponse;
+
+    fn response(status_code: StatusCode, content_length: u32) -> ProbeResponse {
+        ProbeResponse { status_code, content_length, ..Default::default() }
+    }
+
+    #[test]
+    fn dominant_signature_is_suggested_as_filter() {
+        let mut responses = vec![response(StatusCode::OK, 1234); 7];
+        responses.push(response(StatusCode::OK, 88));
+        responses.push(response(StatusCode::FORBIDDEN, 12));
+
+        let suggestion = suggest(responses).unwrap();
+
+        assert_eq!(suggestion.content_length(), 1234);
+        assert_eq!(suggestion.to_string(), "Suggested filter: --filter-content-length 1234 (7 of 9 sampled words returned 200 with 1234 bytes)");
+    }
+
+    #[test]
+    fn no_suggestion_without_a_majority() {
+        let responses = vec![response(StatusCode::OK, 10), response(StatusCode::OK, 20), response(StatusCode::OK, 10), response(StatusCode::NOT_FOUND, 0)];
+
+        assert!(suggest(responses).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use reqwest::StatusCode;
+
+use crate::filters::{similarity, tokens};
+use crate::probe::ProbeResponse;
+
+/// What a response looks like, to tell how far another one is from it.
+struct Signature {
+    status_code: StatusCode,
+    content_length: u32,
+    tokens: HashSet<String>,
+}
+
+impl Signature {
+    fn of(response: &ProbeResponse) -> Self {
+        Self {
+            status_code: response.status_code,
+            content_length: response.content_length,
+            tokens: tokens(&response.body),
+        }
+    }
+
+    /// Zero for an identical response, growing with a different status, length and body words.
+    fn distance(&self, response: &ProbeResponse) -> f64 {
+        let status = match self.status_code == response.status_code {
+            true => 0.0,
+            false => 1.0,
+        };
+        let (a, b) = (self.content_length as f64, response.content_length as f64);
+        let length = (a - b).abs() / a.max(b).max(1.0);
+
+        let body = 1.0 - similarity(&self.tokens, &tokens(&response.body));
+
+        status + length + body
+    }
+}
+
+/// The responses to a known-good and a known-bad word, that other responses are compared against.
+pub struct Canaries {
+    good: Signature,
+    bad: Signature,
+}
+
+impl Canaries {
+    pub fn new(good: &ProbeResponse, bad: &ProbeResponse) -> Self {
+        Self { good: Signature::of(good), bad: Signature::of(bad) }
+    }
+
+    /// Whether `response` is closer to the good canary than to the bad one.
+    pub fn is_good(&self, response: &ProbeResponse) -> bool {
+        self.good.distance(response) < self.bad.distance(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use crate::fuzz::canary::Canaries;
+    use crate::probe::ProbeResponse;
+
+    fn response(body: &str) -> ProbeResponse {
+        ProbeResponse {
+            status_code: StatusCode::OK,
+            content_length: body.len() as u32,
+            body: body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn classifies_by_closest_canary() {
+        let canaries = Canaries::new(
+            &response("<h1>Dashboard</h1><p>Welcome back, manage your account settings here</p>"),
+            &response("<p>Sorry, the page you requested could not be found</p>"),
+        );
+
+        assert!(canaries.is_good(&response("<h1>Dashboard</h1><p>Welcome back, manage your billing settings here</p>")));
+        assert!(!canaries.is_good(&response("<p>Sorry, the page you requested could not be found: /backup</p>")));
+    }
+}
@@ -0,0 +1,66 @@
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+
+use crate::probe::ProbeResponse;
+use crate::Result;
+
+/// Writes the raw request and response of each hit to `<dir>/<word>-<hash>.http`, where the hash of its method and URL
+/// tells apart words that are sanitized to the same name, or the same word on several hosts or methods.
+#[derive(Clone)]
+pub struct Capture {
+    dir: PathBuf,
+}
+
+impl Capture {
+    pub fn new(dir: PathBuf) -> Result<Self> {
+        create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn write(&self, response: &ProbeResponse) -> Result<()> {
+        let contents = format!("### Request\n{}\n\n### Response\n{}\n", response.raw_request, response.render());
+
+        let request = format!("{} {}", response.method, response.request_url);
+
+        write(self.dir.join(format!("{}.http", file_name(&response.word, &request))), contents).map_err(|e| e.into())
+    }
+}
+
+/// The word with anything that could escape the directory or trip up a filesystem replaced, and a short hash of the request.
+fn file_name(word: &str, request: &str) -> String {
+    let name: String = word.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_.".contains(c) { c } else { '_' })
+        .collect();
+    let hash: String = Sha256::digest(request.as_bytes()).iter().take(4).map(|b| format!("{b:02x}")).collect();
+
+    match name.trim_start_matches('.') {
+        "" => format!("_{name}-{hash}"),
+        _ => format!("{name}-{hash}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::capture::file_name;
+
+    #[test]
+    fn file_name_stays_in_directory() {
+        let request = "GET http://localhost/";
+        let hash = &file_name("", request)[2..];
+
+        assert_eq!(file_name("admin.php", request), format!("admin.php-{hash}"));
+        assert_eq!(file_name("../etc/passwd", request), format!(".._etc_passwd-{hash}"));
+        assert_eq!(file_name("..", request), format!("_..-{hash}"));
+        assert_eq!(file_name("a, b", request), format!("a__b-{hash}"));
+        assert_eq!(hash.len(), 8);
+    }
+
+    #[test]
+    fn file_name_tells_requests_apart() {
+        assert_ne!(file_name("a b", "GET http://localhost/a%20b"), file_name("a_b", "GET http://localhost/a_b"));
+        assert_ne!(file_name("admin", "GET http://one/admin"), file_name("admin", "GET http://two/admin"));
+        assert_ne!(file_name("admin", "GET http://one/admin"), file_name("admin", "POST http://one/admin"));
+    }
+}
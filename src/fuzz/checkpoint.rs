@@ -0,0 +1,61 @@
+use std::fs::{read_to_string, rename, write};
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fuzz::stats::Summary;
+use crate::Result;
+
+/// How far a scan of `url` got: the number of words it went through at the URL itself, not counting those of
+/// recursed sub-directories, and its totals until then.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub url: String,
+    pub position: usize,
+    pub summary: Summary,
+}
+
+impl Checkpoint {
+    /// Returns `None` when there is no checkpoint at `path` yet.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match read_to_string(path) {
+            Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes to a temporary file first, so an interrupted save leaves the previous checkpoint intact.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        write(&tmp, serde_json::to_string(self)?)?;
+        rename(tmp, path).map_err(|e| e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_file;
+    use std::path::Path;
+
+    use crate::fuzz::checkpoint::Checkpoint;
+    use crate::fuzz::stats::Summary;
+    use crate::Result;
+
+    #[test]
+    fn save_and_load() -> Result<()> {
+        let path = Path::new("checkpoint_save_and_load.json");
+        assert_eq!(Checkpoint::load(path)?, None);
+
+        let checkpoint = Checkpoint {
+            url: "http://localhost/FUZZ".to_string(),
+            position: 42,
+            summary: Summary { requests: 50, hits: 3, filtered: 47 },
+        };
+        checkpoint.save(path)?;
+
+        assert_eq!(Checkpoint::load(path)?, Some(checkpoint));
+        remove_file(path).map_err(|e| e.into())
+    }
+}
@@ -0,0 +1,94 @@
+use reqwest::StatusCode;
+
+use crate::probe::ProbeResponse;
+
+/// Width of the content length buckets that similar responses fall in.
+const LENGTH_BUCKET: u32 = 50;
+/// Clusters with at least this many hits are collapsed into one line.
+const COLLAPSE_AT: usize = 3;
+
+/// A hit held back until the scan ends, with what it is clustered on.
+pub struct Hit {
+    line: String,
+    status_code: StatusCode,
+    content_length: u32,
+    title: String,
+}
+
+impl Hit {
+    pub fn new(line: String, response: &ProbeResponse) -> Self {
+        Self {
+            line,
+            status_code: response.status_code,
+            content_length: response.content_length,
+            title: response.title(),
+        }
+    }
+
+    fn key(&self) -> (StatusCode, u32, &str) {
+        (self.status_code, self.content_length / LENGTH_BUCKET, &self.title)
+    }
+}
+
+/// Output lines for `hits`, with clusters of similar responses collapsed into their first hit and a count,
+/// in the order their first hit was found.
+pub fn collapse(hits: &[Hit]) -> Vec<String> {
+    let mut clusters: Vec<Vec<&Hit>> = Vec::new();
+    for hit in hits {
+        match clusters.iter_mut().find(|c| c[0].key() == hit.key()) {
+            Some(cluster) => cluster.push(hit),
+            None => clusters.push(vec![hit]),
+        }
+    }
+
+    clusters.into_iter()
+        .flat_map(|cluster| match cluster.len() >= COLLAPSE_AT {
+            true => vec![format!("{}  [+{} similar: {}, ~{} bytes, title {:?}]",
+                                 cluster[0].line, cluster.len() - 1, cluster[0].status_code, cluster[0].content_length, cluster[0].title)],
+            false => cluster.into_iter().map(|h| h.line.clone()).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use crate::fuzz::cluster::{collapse, Hit};
+    use crate::probe::ProbeResponse;
+
+    fn hit(word: &str, body: String) -> Hit {
+        let response = ProbeResponse {
+            status_code: StatusCode::OK,
+            content_length: body.len() as u32,
+            body,
+            ..Default::default()
+        };
+        Hit::new(format!("/{word}"), &response)
+    }
+
+    #[test]
+    fn near_identical_responses_collapse_and_outliers_stand_out() {
+        let not_found = |word: &str| hit(word, format!("<html><title>Not Found</title>{word} does not exist</html>"));
+
+        let hits = vec![
+            not_found("a"),
+            not_found("bb"),
+            hit("admin", format!("<html><title>Admin</title>{}</html>", "x".repeat(500))),
+            not_found("ccc"),
+            not_found("dddd"),
+        ];
+
+        assert_eq!(collapse(&hits), vec![
+            r#"/a  [+3 similar: 200 OK, ~53 bytes, title "Not Found"]"#,
+            "/admin",
+        ]);
+    }
+
+    #[test]
+    fn small_clusters_are_kept() {
+        let hits = vec![hit("a", "same".to_string()), hit("b", "same".to_string())];
+
+        assert_eq!(collapse(&hits), vec!["/a", "/b"]);
+    }
+}
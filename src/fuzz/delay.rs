@@ -0,0 +1,119 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+use crate::{Error, Result};
+
+/// How the delay between requests varies around its mean.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JitterDistribution {
+    /// Anywhere within `jitter` seconds of the mean.
+    Uniform,
+    /// Normally distributed with `jitter` seconds as standard deviation.
+    Gaussian,
+}
+
+impl FromStr for JitterDistribution {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "uniform" => Ok(JitterDistribution::Uniform),
+            "gaussian" => Ok(JitterDistribution::Gaussian),
+            other => Err(format!("invalid jitter distribution `{other}`: expected uniform or gaussian").into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delay {
+    mean: f32,
+    jitter: f32,
+    distribution: JitterDistribution,
+}
+
+impl Delay {
+    pub fn new(mean: f32, jitter: f32, distribution: JitterDistribution) -> Self {
+        Self { mean, jitter, distribution }
+    }
+
+    pub fn with_jitter(self, jitter: f32, distribution: JitterDistribution) -> Self {
+        Self { jitter, distribution, ..self }
+    }
+
+    /// Samples the next delay, never below zero; a jitter that is not a finite, positive number is ignored.
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        let jitter = self.jitter.is_finite() && self.jitter > 0.0 && self.mean.is_finite();
+        let seconds = match (jitter, self.distribution) {
+            (false, _) => self.mean,
+            (true, JitterDistribution::Uniform) => rng.gen_range(self.mean - self.jitter..=self.mean + self.jitter),
+            (true, JitterDistribution::Gaussian) => Normal::new(self.mean, self.jitter).map_or(self.mean, |n| n.sample(rng)),
+        };
+        Duration::try_from_secs_f32(seconds.max(0.0)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::fuzz::delay::{Delay, JitterDistribution};
+
+    fn mean_and_stddev(delay: &Delay) -> (f32, f32) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<f32> = (0..10_000).map(|_| delay.sample(&mut rng).as_secs_f32()).collect();
+
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / samples.len() as f32;
+        (mean, variance.sqrt())
+    }
+
+    #[test]
+    fn gaussian_samples_have_requested_mean_and_stddev() {
+        let (mean, stddev) = mean_and_stddev(&Delay::new(1.0, 0.2, JitterDistribution::Gaussian));
+
+        assert!((mean - 1.0).abs() < 0.01, "mean {mean}");
+        assert!((stddev - 0.2).abs() < 0.01, "stddev {stddev}");
+    }
+
+    #[test]
+    fn uniform_samples_stay_within_jitter() {
+        let delay = Delay::new(1.0, 0.5, JitterDistribution::Uniform);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert!((0..1000).map(|_| delay.sample(&mut rng))
+            .all(|d| Duration::from_millis(500) <= d && d <= Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn samples_are_clamped_to_zero() {
+        let delay = Delay::new(0.0, 1.0, JitterDistribution::Gaussian);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert!((0..1000).map(|_| delay.sample(&mut rng)).any(|d| d.is_zero()));
+    }
+
+    #[test]
+    fn non_finite_jitter_is_ignored() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for jitter in [f32::INFINITY, f32::NAN, -1.0] {
+            for distribution in [JitterDistribution::Uniform, JitterDistribution::Gaussian] {
+                assert_eq!(Delay::new(0.25, jitter, distribution).sample(&mut rng), Duration::from_millis(250));
+            }
+        }
+        assert_eq!(Delay::new(f32::INFINITY, 0.0, JitterDistribution::Uniform).sample(&mut rng), Duration::ZERO);
+    }
+
+    #[test]
+    fn without_jitter_delay_is_constant() {
+        let delay = Delay::new(0.25, 0.0, JitterDistribution::Gaussian);
+
+        assert_eq!(delay.sample(&mut StdRng::seed_from_u64(7)), Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,24 @@
+use std::fs;
+use std::path::Path;
+
+use crate::Result;
+
+/// The words of hits gathered over a scan, in the order they were found, to chain into a follow-up scan.
+#[derive(Default)]
+pub struct HitWords {
+    words: Vec<String>,
+}
+
+impl HitWords {
+    pub fn add(&mut self, word: &str) {
+        if !self.words.iter().any(|w| w == word) {
+            self.words.push(word.to_string());
+        }
+    }
+
+    /// Writes the words one per line, as a wordlist.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let wordlist: String = self.words.iter().map(|w| format!("{w}\n")).collect();
+        fs::write(path, wordlist).map_err(|e| e.into())
+    }
+}
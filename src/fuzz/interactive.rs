@@ -0,0 +1,166 @@
+use std::io::BufRead;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use indicatif::ProgressBar;
+use reqwest::StatusCode;
+use tokio::sync::Notify;
+use tokio::time;
+
+use crate::filters::{FilterUpdate, ProbeResponseFilters};
+
+/// Status code and content length of the most recently reported response.
+pub type LastHit = Arc<Mutex<Option<(StatusCode, u32)>>>;
+
+/// How often a paused scan checks whether it was cancelled, since setting the cancel flag does not wake it.
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// Translates keys typed during a scan into filter updates:
+/// `s` filters the size of the last hit, `c` its status code, and `u` undoes the last update.
+pub struct KeyDispatcher {
+    filters: Arc<RwLock<ProbeResponseFilters>>,
+    last_hit: LastHit,
+    history: Vec<FilterUpdate>,
+}
+
+impl KeyDispatcher {
+    pub fn new(filters: Arc<RwLock<ProbeResponseFilters>>, last_hit: LastHit) -> Self {
+        Self { filters, last_hit, history: Vec::new() }
+    }
+
+    pub fn dispatch(&mut self, key: char) -> Option<FilterUpdate> {
+        let last_hit = *self.last_hit.lock().expect("last hit lock poisoned");
+
+        let update = match key {
+            's' => last_hit.map(|(_, length)| FilterUpdate::AddContentLength(length)),
+            'c' => last_hit.map(|(code, _)| FilterUpdate::AddStatusCode(code)),
+            'u' => self.history.pop().map(undo),
+            _ => None,
+        }?;
+
+        if key != 'u' {
+            self.history.push(update.clone());
+        }
+
+        self.filters.write().expect("filters lock poisoned").apply(&update);
+        Some(update)
+    }
+}
+
+/// Holds back requests while the operator has the scan paused.
+#[derive(Default)]
+pub struct Pause {
+    paused: AtomicBool,
+    resumed: Notify,
+}
+
+impl Pause {
+    pub fn set(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        if !paused {
+            self.resumed.notify_waiters();
+        }
+    }
+
+    /// Returns once the scan is not paused, or once it is `cancelled`.
+    pub async fn wait(&self, cancelled: &AtomicBool) {
+        loop {
+            let resumed = self.resumed.notified();
+            if !self.paused.load(Ordering::SeqCst) || cancelled.load(Ordering::Relaxed) {
+                return;
+            }
+            let _ = time::timeout(CANCEL_POLL, resumed).await;
+        }
+    }
+}
+
+fn undo(update: FilterUpdate) -> FilterUpdate {
+    match update {
+        FilterUpdate::AddStatusCode(code) => FilterUpdate::RemoveStatusCode(code),
+        FilterUpdate::RemoveStatusCode(code) => FilterUpdate::AddStatusCode(code),
+        FilterUpdate::AddContentLength(length) => FilterUpdate::RemoveContentLength(length),
+        FilterUpdate::RemoveContentLength(length) => FilterUpdate::AddContentLength(length),
+    }
+}
+
+/// Reads keys from stdin on a separate thread, so a pending read never blocks shutdown.
+/// Besides the filter keys, `p` pauses the scan and `r` resumes it.
+pub fn spawn(mut dispatcher: KeyDispatcher, pause: Arc<Pause>, pb: ProgressBar) {
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(|l| l.ok()) {
+            for key in line.trim().chars() {
+                match key {
+                    'p' => {
+                        pause.set(true);
+                        pb.set_message("paused");
+                    }
+                    'r' => {
+                        pause.set(false);
+                        pb.set_message("");
+                    }
+                    key => if let Some(update) = dispatcher.dispatch(key) {
+                        pb.suspend(|| eprintln!("[interactive] {update}"));
+                    },
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, RwLock};
+
+    use reqwest::StatusCode;
+
+    use crate::filters::{FilterBody, FilterContentLength, FilterUpdate, ProbeResponseFilters, StatusCodeMatcher};
+    use crate::fuzz::interactive::KeyDispatcher;
+    use crate::probe::ProbeResponse;
+
+    fn response() -> ProbeResponse {
+        ProbeResponse {
+            request_url: "url".to_string(),
+            status_code: StatusCode::OK,
+            content_length: 35,
+            body: "".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn size_key_filters_last_hit_and_undo_restores() {
+        let filters = Arc::new(RwLock::new(ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        )));
+        let last_hit = Arc::new(Mutex::new(Some((StatusCode::OK, 35))));
+        let mut dispatcher = KeyDispatcher::new(filters.clone(), last_hit);
+
+        assert!(filters.read().unwrap().filter(response()).is_some());
+
+        assert_eq!(dispatcher.dispatch('s'), Some(FilterUpdate::AddContentLength(35)));
+        assert_eq!(filters.read().unwrap().filter(response()), None);
+
+        assert_eq!(dispatcher.dispatch('u'), Some(FilterUpdate::RemoveContentLength(35)));
+        assert!(filters.read().unwrap().filter(response()).is_some());
+    }
+
+    #[test]
+    fn keys_without_last_hit_do_nothing() {
+        let filters = Arc::new(RwLock::new(ProbeResponseFilters::new(
+            StatusCodeMatcher::default(),
+            Vec::new(),
+            FilterContentLength::Empty,
+            FilterBody::Empty,
+        )));
+        let mut dispatcher = KeyDispatcher::new(filters, Arc::new(Mutex::new(None)));
+
+        assert_eq!(dispatcher.dispatch('c'), None);
+        assert_eq!(dispatcher.dispatch('u'), None);
+        assert_eq!(dispatcher.dispatch('x'), None);
+    }
+}
@@ -0,0 +1,19 @@
+use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::signal;
+
+/// Turns the first Ctrl-C into setting the returned flag; a second one ends the process as usual.
+pub fn install() -> Arc<AtomicBool> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = interrupted.clone();
+    tokio::spawn(async move {
+        if signal::ctrl_c().await.is_ok() {
+            flag.store(true, Ordering::Relaxed);
+            let _ = signal::ctrl_c().await;
+            process::exit(130);
+        }
+    });
+    interrupted
+}
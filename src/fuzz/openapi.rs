@@ -0,0 +1,117 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use reqwest::{StatusCode, Url};
+
+use crate::probe::ProbeResponse;
+use crate::Result;
+
+/// Hits gathered over a scan, grouped by path and method into a minimal OpenAPI 3 document.
+#[derive(Default)]
+pub struct OpenApi {
+    servers: BTreeSet<String>,
+    paths: BTreeMap<String, BTreeMap<String, BTreeSet<StatusCode>>>,
+}
+
+impl OpenApi {
+    pub fn add(&mut self, response: &ProbeResponse) {
+        let url = match Url::parse(&response.request_url) {
+            Ok(url) => url,
+            Err(_) => return,
+        };
+
+        self.servers.insert(url.origin().ascii_serialization());
+        self.paths.entry(url.path().to_string())
+            .or_default()
+            .entry(response.method.as_str().to_ascii_lowercase())
+            .or_default()
+            .insert(response.status_code);
+    }
+
+    /// The document as YAML, with every string quoted so odd paths stay valid.
+    pub fn to_yaml(&self) -> String {
+        let mut yaml = String::from("openapi: 3.0.3\ninfo:\n  title: \"Discovered by rustbuster\"\n  version: \"0\"\n");
+
+        yaml.push_str("servers:\n");
+        for server in &self.servers {
+            let _ = writeln!(yaml, "  - url: {}", quote(server));
+        }
+
+        yaml.push_str("paths:\n");
+        for (path, methods) in &self.paths {
+            let _ = writeln!(yaml, "  {}:", quote(path));
+            for (method, status_codes) in methods {
+                let _ = writeln!(yaml, "    {method}:\n      responses:");
+                for status_code in status_codes {
+                    let _ = writeln!(yaml, "        \"{}\":\n          description: {}",
+                                     status_code.as_u16(), quote(status_code.canonical_reason().unwrap_or_default()));
+                }
+            }
+        }
+
+        yaml
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_yaml()).map_err(|e| e.into())
+    }
+}
+
+/// A double-quoted YAML scalar; JSON strings are valid ones.
+fn quote(s: &str) -> String {
+    serde_json::Value::from(s).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Method, StatusCode};
+
+    use crate::fuzz::openapi::OpenApi;
+    use crate::probe::ProbeResponse;
+
+    #[test]
+    fn document_lists_discovered_paths_and_methods() {
+        let mut openapi = OpenApi::default();
+        for (path, method, status_code) in [
+            ("/api/users", Method::GET, StatusCode::OK),
+            ("/api/users", Method::POST, StatusCode::CREATED),
+            ("/api/users", Method::GET, StatusCode::UNAUTHORIZED),
+            ("/admin", Method::GET, StatusCode::FORBIDDEN),
+        ] {
+            openapi.add(&ProbeResponse {
+                request_url: format!("http://localhost:8080{path}"),
+                method,
+                status_code,
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(openapi.to_yaml(), "\
+openapi: 3.0.3
+info:
+  title: \"Discovered by rustbuster\"
+  version: \"0\"
+servers:
+  - url: \"http://localhost:8080\"
+paths:
+  \"/admin\":
+    get:
+      responses:
+        \"403\":
+          description: \"Forbidden\"
+  \"/api/users\":
+    get:
+      responses:
+        \"200\":
+          description: \"OK\"
+        \"401\":
+          description: \"Unauthorized\"
+    post:
+      responses:
+        \"201\":
+          description: \"Created\"
+");
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::{create_dir_all, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Serialize;
+use tokio::time;
+
+use crate::{Error, Result};
+
+/// How hits are printed: as lines for people, or as one JSON object per line for other tools.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!("invalid output format `{other}`: expected text or json").into()),
+        }
+    }
+}
+
+/// A word whose request timed out as a JSON line, with `timeout` in place of a status: `{"request_url":…,"status_code":"timeout","word":…}`.
+pub fn timeout_json(request_url: &str, word: &str) -> Result<String> {
+    #[derive(Serialize)]
+    struct Timeout<'a> {
+        request_url: &'a str,
+        status_code: &'a str,
+        word: &'a str,
+    }
+
+    serde_json::to_string(&Timeout { request_url, status_code: "timeout", word }).map_err(|e| e.into())
+}
+
+/// How hits are grouped into files: by exact status code (`200.txt`) or by class (`2xx.txt`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplitBy {
+    Code,
+    Class,
+}
+
+impl FromStr for SplitBy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "code" => Ok(SplitBy::Code),
+            "class" => Ok(SplitBy::Class),
+            other => Err(format!("invalid split `{other}`: expected code or class").into()),
+        }
+    }
+}
+
+/// Writes each hit to a file in `dir` named after its status, opening files as they are first needed.
+pub struct SplitOutput {
+    dir: PathBuf,
+    split_by: SplitBy,
+    writers: Mutex<HashMap<String, BufWriter<File>>>,
+    flush_every_line: AtomicBool,
+}
+
+impl SplitOutput {
+    pub fn new(dir: PathBuf, split_by: SplitBy) -> Result<Self> {
+        create_dir_all(&dir)?;
+        Ok(Self { dir, split_by, writers: Mutex::new(HashMap::new()), flush_every_line: AtomicBool::new(false) })
+    }
+
+    /// Flush after every line when `interval` is zero, otherwise every `interval` until the output is dropped.
+    /// Without it, hits are only guaranteed to be written once the scan ends.
+    pub fn flush_every(self: &Arc<Self>, interval: Duration) {
+        if interval.is_zero() {
+            self.flush_every_line.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        let output = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match output.upgrade() {
+                    Some(output) => { let _ = output.flush(); }
+                    None => break,
+                }
+            }
+        });
+    }
+
+    pub fn write(&self, status_code: StatusCode, line: &str) -> Result<()> {
+        let name = match self.split_by {
+            SplitBy::Code => status_code.as_u16().to_string(),
+            SplitBy::Class => format!("{}xx", status_code.as_u16() / 100),
+        };
+        self.write_to(name, line)
+    }
+
+    /// Writes a word whose request timed out, to `timeout.txt` whatever the split.
+    pub fn write_timeout(&self, line: &str) -> Result<()> {
+        self.write_to("timeout".to_string(), line)
+    }
+
+    fn write_to(&self, name: String, line: &str) -> Result<()> {
+        let mut writers = self.writers.lock().expect("split output lock poisoned");
+        let writer = match writers.entry(name) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let file = File::create(self.dir.join(format!("{}.txt", e.key())))?;
+                e.insert(BufWriter::new(file))
+            }
+        };
+
+        writeln!(writer, "{line}")?;
+        if self.flush_every_line.load(Ordering::Relaxed) {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        for writer in self.writers.lock().expect("split output lock poisoned").values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{read_to_string, remove_dir_all};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use reqwest::StatusCode;
+
+    use crate::fuzz::output::{SplitBy, SplitOutput};
+    use crate::Result;
+
+    #[test]
+    fn split_by_class_groups_codes() -> Result<()> {
+        let dir = PathBuf::from("split_by_class_groups_codes");
+        let output = SplitOutput::new(dir.clone(), SplitBy::Class)?;
+
+        output.write(StatusCode::OK, "/a")?;
+        output.write(StatusCode::NO_CONTENT, "/b")?;
+        output.write(StatusCode::FORBIDDEN, "/c")?;
+        output.flush()?;
+
+        assert_eq!(read_to_string(dir.join("2xx.txt"))?, "/a\n/b\n");
+        assert_eq!(read_to_string(dir.join("4xx.txt"))?, "/c\n");
+        remove_dir_all(dir).map_err(|e| e.into())
+    }
+
+    #[tokio::test]
+    async fn flush_interval_makes_writes_visible() -> Result<()> {
+        let dir = PathBuf::from("flush_interval_makes_writes_visible");
+        let periodic = Arc::new(SplitOutput::new(dir.join("periodic"), SplitBy::Code)?);
+        let per_line = Arc::new(SplitOutput::new(dir.join("per_line"), SplitBy::Code)?);
+        periodic.flush_every(Duration::from_millis(50));
+        per_line.flush_every(Duration::ZERO);
+
+        periodic.write(StatusCode::OK, "/a")?;
+        per_line.write(StatusCode::OK, "/a")?;
+
+        assert_eq!(read_to_string(dir.join("periodic/200.txt"))?, "");
+        assert_eq!(read_to_string(dir.join("per_line/200.txt"))?, "/a\n");
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(read_to_string(dir.join("periodic/200.txt"))?, "/a\n");
+
+        remove_dir_all(dir).map_err(|e| e.into())
+    }
+}
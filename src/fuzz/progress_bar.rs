@@ -1,10 +1,105 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
 
-pub fn new(total: u64) -> ProgressBar {
-    let pb = ProgressBar::new(total);
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::{Error, Result};
+
+/// The stream the progress bar and scan messages are drawn on; hits are printed on the other one,
+/// so redirecting the hit stream captures nothing but hits.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProgressStream {
+    Stdout,
+    Stderr,
+}
+
+impl FromStr for ProgressStream {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stdout" => Ok(ProgressStream::Stdout),
+            "stderr" => Ok(ProgressStream::Stderr),
+            other => Err(format!("invalid progress stream `{other}`: expected stdout or stderr").into()),
+        }
+    }
+}
+
+impl ProgressStream {
+    pub fn draw_target(self) -> ProgressDrawTarget {
+        match self {
+            ProgressStream::Stdout => ProgressDrawTarget::stdout(),
+            ProgressStream::Stderr => ProgressDrawTarget::stderr(),
+        }
+    }
+
+    /// The stream hits are printed on.
+    pub fn hits(self) -> ProgressStream {
+        match self {
+            ProgressStream::Stdout => ProgressStream::Stderr,
+            ProgressStream::Stderr => ProgressStream::Stdout,
+        }
+    }
+
+    pub fn is_terminal(self) -> bool {
+        match self {
+            ProgressStream::Stdout => io::stdout().is_terminal(),
+            ProgressStream::Stderr => io::stderr().is_terminal(),
+        }
+    }
+
+    pub fn println(self, line: &str) {
+        match self {
+            ProgressStream::Stdout => println!("{line}"),
+            ProgressStream::Stderr => eprintln!("{line}"),
+        }
+    }
+}
+
+/// Where notes go: a stream, or nowhere when quiet.
+pub struct Notes(pub Option<ProgressStream>);
+
+impl Notes {
+    pub fn println(&self, line: &str) {
+        if let Some(stream) = self.0 {
+            stream.println(line);
+        }
+    }
+}
+
+/// A bar drawn on `stream`, or a hidden one that still counts when `show` is off or the stream is not a terminal.
+pub fn new(total: u64, stream: ProgressStream, show: bool) -> ProgressBar {
+    if !show || !stream.is_terminal() {
+        let pb = ProgressBar::hidden();
+        pb.set_length(total);
+        return pb;
+    }
+
+    let pb = ProgressBar::with_draw_target(Some(total), stream.draw_target());
     pb.set_style(ProgressStyle::with_template(
         "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
         .unwrap()
         .progress_chars("##-"));
     pb
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::progress_bar::{self, ProgressStream};
+
+    #[test]
+    fn hits_go_to_the_other_stream() {
+        assert_eq!("stdout".parse::<ProgressStream>().unwrap().hits(), ProgressStream::Stderr);
+        assert_eq!("stderr".parse::<ProgressStream>().unwrap().hits(), ProgressStream::Stdout);
+        assert!("tty".parse::<ProgressStream>().is_err());
+    }
+
+    #[test]
+    fn hidden_bar_still_counts() {
+        let pb = progress_bar::new(10, ProgressStream::Stderr, false);
+        pb.inc(3);
+
+        assert!(pb.is_hidden());
+        assert_eq!((pb.position(), pb.length()), (3, Some(10)));
+    }
+}
@@ -0,0 +1,28 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::{self, Instant};
+
+/// Spaces requests evenly, so that no more than `rate` go out per second whatever the number of threads.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// `None` for a rate of 0, which is unlimited.
+    pub fn new(rate: u32) -> Option<Self> {
+        (rate > 0).then(|| Self { interval: Duration::from_secs(1) / rate, next: Mutex::new(None) })
+    }
+
+    /// Waits for the next free slot.
+    pub async fn acquire(&self) {
+        let at = {
+            let mut next = self.next.lock().expect("rate limiter lock poisoned");
+            let at = next.map_or(Instant::now(), |next| next.max(Instant::now()));
+            *next = Some(at + self.interval);
+            at
+        };
+        time::sleep_until(at).await;
+    }
+}
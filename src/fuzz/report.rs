@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::probe::ProbeResponse;
+use crate::Result;
+
+/// Hits gathered over a scan, written as a single JSON document once it ends.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Report {
+    pub hits: Vec<Entry>,
+    /// The methods each directory allows, when enumerated with OPTIONS.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub methods: BTreeMap<String, Vec<String>>,
+    /// The default `user:pass` pairs each endpoint accepted, when tried with --default-creds.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub credentials: BTreeMap<String, Vec<String>>,
+    /// The URLs whose request timed out, when reported as hits with --timeout-as-hit.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeouts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct Entry {
+    pub url: String,
+    pub word: String,
+    pub method: String,
+    pub status: u16,
+    pub length: u32,
+}
+
+impl Report {
+    pub fn add(&mut self, response: &ProbeResponse) {
+        self.hits.push(Entry {
+            url: response.request_url.clone(),
+            word: response.word.clone(),
+            method: response.method.to_string(),
+            status: response.status_code.as_u16(),
+            length: response.content_length,
+        });
+    }
+
+    pub fn add_methods(&mut self, directory: &str, methods: Vec<String>) {
+        self.methods.insert(directory.to_string(), methods);
+    }
+
+    pub fn add_timeout(&mut self, url: &str) {
+        self.timeouts.push(url.to_string());
+    }
+
+    pub fn add_credentials(&mut self, url: &str, credentials: Vec<String>) {
+        self.credentials.insert(url.to_string(), credentials);
+    }
+
+    /// Writes the report indented when `pretty`, and on a single line otherwise.
+    pub fn write(&self, writer: impl Write, pretty: bool) -> Result<()> {
+        match pretty {
+            true => serde_json::to_writer_pretty(writer, self),
+            false => serde_json::to_writer(writer, self),
+        }.map_err(|e| e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::StatusCode;
+
+    use crate::fuzz::report::Report;
+    use crate::probe::ProbeResponse;
+    use crate::Result;
+
+    #[test]
+    fn pretty_report_is_indented_and_parses_back() -> Result<()> {
+        let mut report = Report::default();
+        report.add(&ProbeResponse {
+            request_url: "http://localhost/admin".to_string(),
+            word: "admin".to_string(),
+            status_code: StatusCode::FORBIDDEN,
+            content_length: 12,
+            ..Default::default()
+        });
+
+        let mut compact = Vec::new();
+        report.write(&mut compact, false)?;
+        let mut pretty = Vec::new();
+        report.write(&mut pretty, true)?;
+        let pretty = String::from_utf8(pretty).unwrap();
+
+        assert!(!compact.contains(&b'\n'));
+        assert!(pretty.contains("\n  \"hits\": [\n    {\n      \"url\": \"http://localhost/admin\""), "{pretty}");
+        assert_eq!(serde_json::from_str::<Report>(&pretty)?, report);
+        assert_eq!(serde_json::from_slice::<Report>(&compact)?, report);
+        Ok(())
+    }
+}
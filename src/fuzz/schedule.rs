@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+/// The order in which queued work, such as discovered directories, is taken up.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Schedule {
+    /// Oldest first, which scans breadth-first.
+    #[default]
+    Fifo,
+    /// Newest first, which scans depth-first.
+    Lifo,
+}
+
+impl FromStr for Schedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "fifo" => Ok(Schedule::Fifo),
+            "lifo" => Ok(Schedule::Lifo),
+            other => Err(format!("invalid schedule `{other}`: expected fifo or lifo").into()),
+        }
+    }
+}
+
+pub struct WorkQueue<T> {
+    schedule: Schedule,
+    items: VecDeque<T>,
+    capacity: Option<usize>,
+}
+
+impl<T> WorkQueue<T> {
+    pub fn new(schedule: Schedule) -> Self {
+        Self { schedule, items: VecDeque::new(), capacity: None }
+    }
+
+    /// Bound the queue to `capacity` entries, no bound when `None`.
+    pub fn with_capacity(mut self, capacity: Option<usize>) -> Self {
+        self.capacity = capacity.map(|c| c.max(1));
+        self
+    }
+
+    /// Queues `item`; when the queue is full, drops whichever entry would be taken up last and returns it.
+    pub fn push(&mut self, item: T) -> Option<T> {
+        if self.capacity.is_none_or(|c| self.items.len() < c) {
+            self.items.push_back(item);
+            return None;
+        }
+
+        match self.schedule {
+            Schedule::Fifo => Some(item),
+            Schedule::Lifo => {
+                let dropped = self.items.pop_front();
+                self.items.push_back(item);
+                dropped
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        match self.schedule {
+            Schedule::Fifo => self.items.pop_front(),
+            Schedule::Lifo => self.items.pop_back(),
+        }
+    }
+
+    /// Takes every entry out, in the order they were queued.
+    pub fn drain(&mut self) -> impl Iterator<Item=T> + '_ {
+        self.items.drain(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::schedule::{Schedule, WorkQueue};
+
+    /// Visits a tree of directories the way recursion does: each scanned directory queues its children.
+    fn visit(schedule: Schedule) -> Vec<&'static str> {
+        visit_bounded(schedule, None).0
+    }
+
+    /// Like `visit`, with the queue bounded to `capacity`; also returns the longest the queue got.
+    fn visit_bounded(schedule: Schedule, capacity: Option<usize>) -> (Vec<&'static str>, usize) {
+        let children = |dir: &str| match dir {
+            "" => vec!["a/", "b/"],
+            "a/" => vec!["a/x/"],
+            "a/x/" => vec!["a/x/y/"],
+            "b/" => vec!["b/z/"],
+            _ => vec![],
+        };
+
+        let mut queue = WorkQueue::new(schedule).with_capacity(capacity);
+        queue.push("");
+        let mut visited = Vec::new();
+        let mut longest = 0;
+        while let Some(dir) = queue.pop() {
+            visited.push(dir);
+            for child in children(dir) {
+                queue.push(child);
+                longest = longest.max(queue.items.len());
+            }
+        }
+        (visited, longest)
+    }
+
+    #[test]
+    fn lifo_descends_depth_first() {
+        assert_eq!(visit(Schedule::Lifo), vec!["", "b/", "b/z/", "a/", "a/x/", "a/x/y/"]);
+        assert_eq!(visit(Schedule::Fifo), vec!["", "a/", "b/", "a/x/", "b/z/", "a/x/y/"]);
+        assert!("random".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn bounded_queue_drops_what_would_be_taken_up_last() {
+        assert_eq!(visit_bounded(Schedule::Fifo, Some(1)), (vec!["", "a/", "a/x/", "a/x/y/"], 1));
+        assert_eq!(visit_bounded(Schedule::Lifo, Some(1)), (vec!["", "b/", "b/z/"], 1));
+        assert_eq!(visit_bounded(Schedule::Fifo, Some(2)).0.len(), 6);
+    }
+}
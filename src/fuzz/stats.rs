@@ -0,0 +1,138 @@
+use std::fmt::{Display, Formatter};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Totals of a scan, carried over when it is resumed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub requests: usize,
+    pub hits: usize,
+    pub filtered: usize,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Requests: {}  Hits: {}  Filtered: {}", self.requests, self.hits, self.filtered)
+    }
+}
+
+#[derive(Default)]
+pub struct Counters {
+    requests: AtomicUsize,
+    hits: AtomicUsize,
+    filtered: AtomicUsize,
+}
+
+impl From<Summary> for Counters {
+    fn from(summary: Summary) -> Self {
+        Self {
+            requests: AtomicUsize::new(summary.requests),
+            hits: AtomicUsize::new(summary.hits),
+            filtered: AtomicUsize::new(summary.filtered),
+        }
+    }
+}
+
+impl Counters {
+    pub fn request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn filtered(&self) {
+        self.filtered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary {
+            requests: self.requests.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            filtered: self.filtered.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Default)]
+struct WorkerStat {
+    requests: AtomicUsize,
+    current: Mutex<Option<String>>,
+}
+
+/// Requests sent and the word being probed by each of the concurrent workers, one per permit.
+pub struct WorkerStats {
+    workers: Vec<WorkerStat>,
+    idle: Mutex<Vec<usize>>,
+}
+
+impl WorkerStats {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            workers: (0..threads).map(|_| WorkerStat::default()).collect(),
+            idle: Mutex::new((0..threads).rev().collect()),
+        }
+    }
+
+    /// Claims an idle worker; there is one for every permit handed out.
+    pub fn checkout(&self) -> usize {
+        self.idle.lock().expect("worker stats lock poisoned").pop().expect("a worker is idle for every permit")
+    }
+
+    pub fn checkin(&self, worker: usize) {
+        *self.workers[worker].current.lock().expect("worker stats lock poisoned") = None;
+        self.idle.lock().expect("worker stats lock poisoned").push(worker);
+    }
+
+    pub fn record(&self, worker: usize, word: String) {
+        let stat = &self.workers[worker];
+        stat.requests.fetch_add(1, Ordering::Relaxed);
+        *stat.current.lock().expect("worker stats lock poisoned") = Some(word);
+    }
+
+    pub fn total(&self) -> usize {
+        self.workers.iter().map(|w| w.requests.load(Ordering::Relaxed)).sum()
+    }
+
+    /// The words currently being probed, by worker.
+    pub fn live(&self) -> String {
+        self.workers.iter().enumerate()
+            .filter_map(|(i, w)| w.current.lock().expect("worker stats lock poisoned").as_ref().map(|word| format!("#{i}: {word}")))
+            .collect::<Vec<String>>()
+            .join("  ")
+    }
+
+    pub fn summary(&self) -> String {
+        self.workers.iter().enumerate()
+            .map(|(i, w)| format!("Worker #{i:<3} {:>8} requests", w.requests.load(Ordering::Relaxed)))
+            .chain(std::iter::once(format!("Total       {:>8} requests", self.total())))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::stats::WorkerStats;
+
+    #[test]
+    fn idle_workers_are_reused() {
+        let stats = WorkerStats::new(2);
+
+        let first = stats.checkout();
+        let second = stats.checkout();
+        assert_ne!(first, second);
+
+        stats.record(first, "admin".to_string());
+        stats.record(second, "login".to_string());
+        assert_eq!(stats.live(), "#0: admin  #1: login");
+
+        stats.checkin(first);
+        assert_eq!(stats.checkout(), first);
+        assert_eq!(stats.live(), "#1: login");
+        assert_eq!(stats.total(), 2);
+    }
+}
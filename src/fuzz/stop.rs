@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::{Error, Result};
+use crate::probe::ProbeResponse;
+
+/// A word or URL that ends the scan once it is hit, given literally or as a regex matching it entirely.
+/// Patterns that are not valid regexes only match literally.
+#[derive(Clone, Debug)]
+pub struct StopPattern {
+    literal: String,
+    regex: Option<Regex>,
+}
+
+impl FromStr for StopPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(StopPattern {
+            literal: s.to_string(),
+            regex: Regex::new(&format!("^(?:{s})$")).ok(),
+        })
+    }
+}
+
+impl StopPattern {
+    pub fn matches(&self, response: &ProbeResponse) -> bool {
+        [&response.word, &response.request_url].iter()
+            .any(|s| **s == self.literal || self.regex.as_ref().is_some_and(|r| r.is_match(s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fuzz::stop::StopPattern;
+    use crate::probe::ProbeResponse;
+
+    fn response(word: &str) -> ProbeResponse {
+        ProbeResponse {
+            request_url: format!("http://localhost/{word}"),
+            word: word.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matches_word_or_url_literally_or_entirely_by_regex() {
+        let literal: StopPattern = "admin".parse().unwrap();
+        let regex: StopPattern = r"backup\.(zip|tar)".parse().unwrap();
+        let url: StopPattern = "http://localhost/(.*)".parse().unwrap();
+
+        assert!(literal.matches(&response("admin")));
+        assert!(!literal.matches(&response("administrator")));
+        assert!(regex.matches(&response("backup.tar")));
+        assert!(!regex.matches(&response("backup.tar.gz")));
+        assert!(url.matches(&response("anything")));
+    }
+
+    #[test]
+    fn invalid_regex_matches_literally() {
+        let pattern: StopPattern = "a(b".parse().unwrap();
+
+        assert!(pattern.matches(&response("a(b")));
+        assert!(!pattern.matches(&response("ab")));
+    }
+}
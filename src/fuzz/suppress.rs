@@ -0,0 +1,64 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use reqwest::StatusCode;
+use reqwest::header::ETAG;
+
+use crate::probe::ProbeResponse;
+
+/// Share of the requests so far from which a status and length are too common to be interesting.
+const COMMON_SHARE: f64 = 0.02;
+/// Hits a signature needs before it can be called common, so the first few responses are never hidden.
+const COMMON_AFTER: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub enum Commonness {
+    Rare,
+    /// This hit made its signature common; it and the ones after it are hidden.
+    BecameCommon,
+    Common,
+}
+
+/// A running histogram of the status codes and content lengths of hits.
+pub struct CommonSignatures {
+    counts: Mutex<HashMap<(StatusCode, u32), (usize, bool)>>,
+}
+
+impl CommonSignatures {
+    pub fn new() -> Self {
+        Self { counts: Mutex::new(HashMap::new()) }
+    }
+
+    /// Counts `response` out of the `requests` sent so far.
+    pub fn record(&self, response: &ProbeResponse, requests: usize) -> Commonness {
+        let mut counts = self.counts.lock().expect("signature counts lock poisoned");
+        let (count, common) = counts.entry((response.status_code, response.content_length)).or_default();
+        *count += 1;
+
+        match *common {
+            true => Commonness::Common,
+            false if *count >= COMMON_AFTER && *count as f64 > COMMON_SHARE * requests as f64 => {
+                *common = true;
+                Commonness::BecameCommon
+            }
+            false => Commonness::Rare,
+        }
+    }
+}
+
+/// The `ETag`s of the hits so far; paths that share one serve the same resource.
+pub struct SeenEtags {
+    etags: Mutex<HashSet<String>>,
+}
+
+impl SeenEtags {
+    pub fn new() -> Self {
+        Self { etags: Mutex::new(HashSet::new()) }
+    }
+
+    /// Whether `response` has no `ETag`, or is the first with its `ETag`.
+    pub fn is_first(&self, response: &ProbeResponse) -> bool {
+        let Some(etag) = response.headers.get(ETAG).map(|e| String::from_utf8_lossy(e.as_bytes()).to_string()) else { return true };
+        self.etags.lock().expect("etags lock poisoned").insert(etag)
+    }
+}
@@ -1,3 +1,8 @@
+use std::fs::{File, OpenOptions};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
 use clap::Parser;
 
 pub use self::error::{Error, Result};
@@ -8,44 +13,269 @@ mod filters;
 mod words;
 mod probe;
 mod error;
+mod rng;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = cli::Cli::parse();
 
-    let mut wordlist = words::Wordlist::try_from(args.wordlist)?;
-    wordlist.set_extensions(args.extensions);
-
-    let http_probe = probe::HttpProbe::builder()
-        .with_url(args.url)
-        .with_method(args.method)
-        .with_headers(args.headers)
-        .build()?;
-
-    let filters = filters::ProbeResponseFilters::new(
-        args.filter_status_codes,
-        args.filter_content_length,
-        args.filter_body,
-    );
-
-    let fuzzer = fuzz::HttpFuzzer::new(
-        http_probe,
-        filters,
-        args.delay,
-        args.verbose,
-    );
-
-    fuzzer.brute_force(wordlist).await
+    let mut wordlists = Vec::new();
+    if let Some(command) = &args.input_cmd {
+        wordlists.push((probe::FUZZ.to_string(), words::Wordlist::from_command(command, args.max_wordlist_memory * 1024 * 1024).await?));
+    }
+    for (keyword, filename) in args.wordlist.iter() {
+        wordlists.push((keyword.clone(), words::Wordlist::try_from(filename.clone())?));
+    }
+
+    let mut wordlists = words::Wordlists::try_from(wordlists)?;
+    wordlists.set_extensions(args.extensions.clone());
+    wordlists.set_mutations(args.mutations.clone());
+    wordlists.set_word_subs(args.word_sub.clone());
+    wordlists.set_stable_order(args.stable_order);
+    wordlists.set_word_length(args.min_word_length, args.max_word_length);
+    wordlists.set_lossy(args.lossy_words);
+    if !args.lossy_words {
+        let invalid = wordlists.invalid_lines();
+        if invalid > 0 {
+            eprintln!("Warning: skipping {invalid} wordlist lines that are not valid UTF-8; use --lossy-words to decode them");
+        }
+    }
+    wordlists.set_max_memory(args.max_wordlist_memory * 1024 * 1024);
+    wordlists.set_order(args.order)?;
+    if args.backups {
+        wordlists.add_suffixes(words::BACKUP_SUFFIXES);
+    }
+
+    let errors = args.save_errors.as_ref()
+        .map(|path| OpenOptions::new().create(true).append(true).open(path))
+        .transpose()?;
+
+    let output = args.output.as_ref().map(File::create).transpose()?;
+
+    let signer = args.hmac_header.as_ref()
+        .zip(args.hmac_secret.as_ref())
+        .map(|(header, secret)| probe::signing::HmacSigner::new(header.clone(), secret, args.hmac_parts.clone()));
+
+    let split_output = args.split_output.as_ref()
+        .map(|dir| fuzz::output::SplitOutput::new(dir.clone(), args.split_by).map(Arc::new))
+        .transpose()?;
+
+    if let (Some(split_output), Some(ms)) = (&split_output, args.flush_interval) {
+        split_output.flush_every(Duration::from_millis(ms));
+    }
+
+    let capture = args.capture_dir.as_ref()
+        .map(|dir| fuzz::capture::Capture::new(dir.clone()))
+        .transpose()?;
+
+    let openapi = args.export_openapi.as_ref().map(|_| Arc::new(Mutex::new(fuzz::openapi::OpenApi::default())));
+
+    let hit_words = args.hits_wordlist.as_ref().map(|_| Arc::new(Mutex::new(fuzz::hits::HitWords::default())));
+
+    let report = args.report.as_ref().map(|_| Arc::new(Mutex::new(fuzz::report::Report::default())));
+
+    let resume = match (&args.checkpoint, args.resume) {
+        (Some(path), true) => fuzz::checkpoint::Checkpoint::load(path)?,
+        _ => None,
+    };
+
+    let rng = rng::shared(args.seed);
+    let interrupted = fuzz::interrupt::install();
+
+    for url in args.targets().iter() {
+        if interrupted.load(Ordering::Relaxed) {
+            break;
+        }
+        let http_probe = probe::HttpProbe::builder()
+            .with_url(url.clone())
+            .with_method(args.method.clone())
+            .with_method_override(args.method_override.clone())
+            .with_headers(args.headers.clone())
+            .with_basic_auth(args.basic_auth.clone())
+            .with_bearer(args.bearer.clone())
+            .with_cookies(args.cookie.clone())
+            .with_fuzz_header(args.fuzz_header.clone())
+            .with_body(args.data.clone())
+            .with_body_per_line(args.body_per_line)
+            .with_chunked(args.chunked)
+            .with_replacements(args.replacements.clone())
+            .with_signer(signer.clone())
+            .with_digest_auth(args.digest_auth.clone())
+            .with_ca_certs(args.ca_cert.clone())
+            .with_proxy(args.proxy.clone())
+            .with_doh(args.doh.clone())
+            .with_connect_to(args.connect_to.clone())
+            .with_token_endpoint(args.token_endpoint.clone(), Duration::from_secs(args.token_refresh_interval))
+            .with_max_connections(args.max_connections)
+            .with_timeout(Duration::from_secs_f32(args.timeout))
+            .with_redirects(args.follow_redirects)
+            .with_max_header_bytes(args.max_header_bytes)
+            .with_warn_path_rewrites(args.warn_path_rewrites)
+            .with_random_case(args.random_case.then(|| rng.clone()))
+            .with_keywords(wordlists.keywords())
+            .build()?;
+        http_probe.start_token_refresh().await?;
+
+        if let Some(expected) = args.match_favicon_hash {
+            match http_probe.favicon_hash().await.ok().flatten() {
+                Some(hash) if hash == expected => (),
+                hash => {
+                    eprintln!("Skipping {url}: favicon hash {hash:?} does not match {expected}");
+                    continue;
+                }
+            }
+        }
+
+        if let Some(mime) = &args.expect_content_type {
+            http_probe.health_check(mime).await?;
+        }
+
+        if args.detect_waf {
+            if let Some(waf) = http_probe.detect_waf().await.ok().flatten() {
+                eprintln!("Warning: {url} appears to be behind {waf}; results may be affected");
+            }
+        }
+
+        if args.seed_from_robots {
+            let seeds = match wordlists.keywords().first() {
+                Some(keyword) => http_probe.seed_words(keyword).await,
+                None => Vec::new(),
+            };
+            wordlists.set_seeds(seeds)?;
+        }
+
+        let filters = filters::ProbeResponseFilters::new(
+            args.filter_status_codes.clone(),
+            args.match_status_codes.clone(),
+            args.filter_content_length()?,
+            args.filter_body.clone(),
+        )
+            .with_match_content_length(args.match_content_length()?)
+            .with_filter_word_count(args.filter_words()?)
+            .with_filter_line_count(args.filter_lines()?)
+            .with_header_content_length_only(args.content_length_from_header_only)
+            .with_match_response_time(args.match_response_time)
+            .with_filter_response_time(args.filter_response_time())
+            .with_match_ttfb(args.match_ttfb)
+            .with_filter_ttfb(args.filter_ttfb)
+            .with_filter_hashes(args.filter_hash.clone())
+            .with_match_set_cookie(args.match_set_cookie.clone())
+            .with_match_mode(args.match_mode)
+            .with_normalize_trailing_whitespace(args.trailing_newline_normalization)
+            .with_normalize_body_whitespace(args.normalize_whitespace);
+
+        let fuzzer = fuzz::HttpFuzzer::new(
+            http_probe,
+            filters,
+            args.delay,
+            args.verbose,
+        )
+            .with_jitter(args.jitter, args.jitter_dist)
+            .with_rng(rng.clone())
+            .with_threads(args.threads)
+            .with_rate(args.rate)
+            .with_schedule(args.schedule)
+            .with_queue_capacity(args.queue_capacity)
+            .with_recursion(args.recursive, args.recursion_depth)
+            .with_ramp_up(args.ramp_up)
+            .with_adaptive_concurrency(args.adaptive_concurrency)
+            .with_timeout_budget(args.timeout_budget)
+            .with_timeout_as_hit(args.timeout_as_hit)
+            .with_host_failure_threshold(args.host_failure_threshold)
+            .with_auto_suppress_common(args.auto_suppress_common)
+            .with_dedupe_etag(args.dedupe_etag)
+            .with_retries(args.retries, Duration::from_millis(args.retry_backoff))
+            .with_retry_status_codes(args.retry_status_codes.clone())
+            .with_smart_extensions(args.smart_extensions)
+            .with_show_word(args.show_word)
+            .with_show_snippet(args.show_snippet)
+            .with_show_encoding(args.response_encoding_detection)
+            .with_emit_curl(args.emit_curl)
+            .with_interactive(args.interactive_filter)
+            .with_report_length_mismatch(args.content_length_from_header_only)
+            .with_cache(args.cache)
+            .with_save_errors(errors.as_ref().map(File::try_clone).transpose()?)
+            .with_output(output.as_ref().map(File::try_clone).transpose()?)
+            .with_split_output(split_output.clone())
+            .with_capture(capture.clone())
+            .with_openapi(openapi.clone())
+            .with_hit_words(hit_words.clone())
+            .with_report(report.clone())
+            .with_checkpoint(args.checkpoint.clone())
+            .with_resume(resume.clone().filter(|c| c.url == url.as_str()))
+            .with_stop_after_hit(args.stop_after_hit.clone())
+            .with_cancel(interrupted.clone())
+            .with_progress_stream(match args.output_format {
+                fuzz::output::OutputFormat::Text => args.progress_stream,
+                fuzz::output::OutputFormat::Json => fuzz::progress_bar::ProgressStream::Stderr,
+            })
+            .with_progress(!args.no_progress)
+            .with_quiet(args.quiet)
+            .with_output_format(args.output_format)
+            .with_cluster_output(args.cluster_output)
+            .with_canaries(args.canary_good.clone(), args.canary_bad.clone())
+            .with_smart_404(args.smart_404)
+            .with_auto_calibrate(args.auto_calibrate)
+            .with_two_pass(args.two_pass, args.apply_suggested)
+            .with_well_known(args.well_known)
+            .with_enumerate_methods(args.enumerate_methods)
+            .with_default_creds(args.default_creds)
+            .with_confirm_case(args.confirm_case)
+            .with_dry_run(args.dry_run);
+
+        Arc::new(fuzzer).brute_force(&wordlists).await?;
+    }
+
+    if let (Some(path), Some(report)) = (&args.report, &report) {
+        report.lock().expect("report lock poisoned").write(File::create(path)?, args.output_json_pretty)?;
+    }
+
+    if let (Some(path), Some(openapi)) = (&args.export_openapi, &openapi) {
+        openapi.lock().expect("openapi lock poisoned").write(path)?;
+    }
+
+    if let (Some(path), Some(hit_words)) = (&args.hits_wordlist, &hit_words) {
+        hit_words.lock().expect("hit words lock poisoned").write(path)?;
+    }
+
+    Ok(())
 }
 
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
+
     use crate::cli::Cli;
+    use crate::probe::HttpProbe;
+    use crate::Result;
+    use crate::words::Payload;
 
     #[test]
     fn verify_cli() {
         use clap::CommandFactory;
         Cli::command().debug_assert()
     }
+
+    #[tokio::test]
+    async fn ports_scan_every_port_of_a_host() -> Result<()> {
+        let mut first = mockito::Server::new_async().await;
+        let mut second = mockito::Server::new_async().await;
+        let first_mock = first.mock("GET", "/admin").create_async().await;
+        let second_mock = second.mock("GET", "/admin").create_async().await;
+        let ports = format!("{},{}", first.socket_address().port(), second.socket_address().port());
+
+        let args = Cli::parse_from(["rustbuster", "-u", "http://127.0.0.1/FUZZ", "-w", "words.txt", "--ports", &ports]);
+
+        let targets = args.targets();
+        assert_eq!(targets.len(), 2);
+
+        for url in targets {
+            HttpProbe::builder().with_url(url).build()?.probe(&Payload::from("admin")).await?;
+        }
+
+        first_mock.assert_async().await;
+        second_mock.assert_async().await;
+        Ok(())
+    }
 }
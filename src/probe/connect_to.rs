@@ -0,0 +1,73 @@
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+
+use reqwest::Url;
+
+use crate::{Error, Result};
+
+/// Sends requests for `host:port` to `target_host:target_port`, keeping the original Host header and SNI,
+/// like curl's `--connect-to`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectTo {
+    pub host: String,
+    pub port: u16,
+    target_host: String,
+    target_port: u16,
+}
+
+impl FromStr for ConnectTo {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::from(format!("invalid connect-to `{s}`: expected HOST:PORT:TARGET_HOST:TARGET_PORT"));
+
+        match s.split(':').collect::<Vec<&str>>()[..] {
+            [host, port, target_host, target_port] if !host.is_empty() && !target_host.is_empty() => Ok(ConnectTo {
+                host: host.to_ascii_lowercase(),
+                port: port.parse().map_err(|_| invalid())?,
+                target_host: target_host.to_string(),
+                target_port: target_port.parse().map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl ConnectTo {
+    pub fn matches(&self, url: &Url) -> bool {
+        url.host_str() == Some(self.host.as_str()) && url.port_or_known_default() == Some(self.port)
+    }
+
+    /// The address the host resolves to instead; its port is ignored, as the URL's is used.
+    pub fn target(&self) -> Result<SocketAddr> {
+        (self.target_host.as_str(), self.target_port).to_socket_addrs()?
+            .next()
+            .ok_or_else(|| format!("connect-to target {} does not resolve", self.target_host).into())
+    }
+
+    /// `url` with the target port, which is where the connection goes once the host resolves to the target.
+    pub fn redirect(&self, url: &Url) -> Url {
+        let mut url = url.clone();
+        let _ = url.set_port(Some(self.target_port));
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::probe::connect_to::ConnectTo;
+
+    #[test]
+    fn parse_connect_to() {
+        let connect_to = "Example.com:443:127.0.0.1:8443".parse::<ConnectTo>().unwrap();
+
+        assert_eq!(connect_to.host, "example.com");
+        assert_eq!(connect_to.port, 443);
+        assert!(connect_to.matches(&"https://example.com/FUZZ".parse().unwrap()));
+        assert!(!connect_to.matches(&"http://example.com/FUZZ".parse().unwrap()));
+        assert_eq!(connect_to.redirect(&"https://example.com/admin".parse().unwrap()).as_str(), "https://example.com:8443/admin");
+
+        assert!("example.com:443".parse::<ConnectTo>().is_err());
+        assert!("example.com:https:127.0.0.1:8443".parse::<ConnectTo>().is_err());
+    }
+}
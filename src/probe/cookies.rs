@@ -0,0 +1,84 @@
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+
+use crate::{Error, Result};
+
+/// A `NAME=VALUE` cookie given on the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+}
+
+impl FromStr for Cookie {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => Ok(Cookie { name: name.trim().to_string(), value: value.trim().to_string() }),
+            _ => Err(format!("invalid cookie `{s}`: expected NAME=VALUE").into()),
+        }
+    }
+}
+
+/// The cookies of a session: sent with every request and updated from the `Set-Cookie` headers of responses.
+/// Unlike a browser's, it keeps one cookie per name, whatever its domain or path.
+pub struct CookieJar {
+    cookies: Mutex<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    pub fn new(cookies: Vec<Cookie>) -> Self {
+        Self { cookies: Mutex::new(cookies) }
+    }
+
+    /// The `Cookie` header for the jar, after the cookies of `existing`, such as a header given with `-H`.
+    pub fn header(&self, existing: Option<&HeaderValue>) -> Result<HeaderValue> {
+        let cookies = self.cookies.lock().expect("cookie jar lock poisoned");
+        let pairs = existing.and_then(|h| h.to_str().ok()).into_iter()
+            .map(str::to_string)
+            .chain(cookies.iter().map(|c| format!("{}={}", c.name, c.value)));
+
+        Ok(pairs.collect::<Vec<String>>().join("; ").parse()?)
+    }
+
+    /// Keeps the cookies the response sets, and drops the ones it expires with `Max-Age=0`.
+    pub fn store(&self, headers: &HeaderMap) {
+        let mut cookies = self.cookies.lock().expect("cookie jar lock poisoned");
+        for set_cookie in headers.get_all(SET_COOKIE).iter().filter_map(|h| h.to_str().ok()) {
+            let mut attributes = set_cookie.split(';');
+            let Some(Ok(cookie)) = attributes.next().map(Cookie::from_str) else { continue };
+            let expired = attributes.any(|a| a.trim().eq_ignore_ascii_case("max-age=0"));
+
+            cookies.retain(|c| c.name != cookie.name);
+            if !expired {
+                cookies.push(cookie);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderMap, SET_COOKIE};
+
+    use crate::probe::cookies::{Cookie, CookieJar};
+
+    #[test]
+    fn jar_keeps_set_cookies_and_drops_expired_ones() {
+        let jar = CookieJar::new(vec!["session=abc".parse().unwrap(), "theme=dark".parse().unwrap()]);
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, "session=def; Path=/; HttpOnly".parse().unwrap());
+        headers.append(SET_COOKIE, "theme=; Max-Age=0".parse().unwrap());
+        headers.append(SET_COOKIE, "csrf=123".parse().unwrap());
+        jar.store(&headers);
+
+        assert_eq!(jar.header(None).unwrap(), "session=def; csrf=123");
+        assert_eq!(jar.header(Some(&"lang=en".parse().unwrap())).unwrap(), "lang=en; session=def; csrf=123");
+        assert!("=value".parse::<Cookie>().is_err());
+        assert!("novalue".parse::<Cookie>().is_err());
+    }
+}
@@ -0,0 +1,19 @@
+/// Common vendor and installer defaults, tried against the endpoints a scan finds behind HTTP Basic.
+pub const DEFAULT_CREDENTIALS: &[(&str, &str)] = &[
+    ("admin", "admin"),
+    ("admin", "password"),
+    ("admin", "123456"),
+    ("admin", ""),
+    ("root", "root"),
+    ("root", "toor"),
+    ("administrator", "administrator"),
+    ("user", "user"),
+    ("guest", "guest"),
+    ("test", "test"),
+    ("tomcat", "tomcat"),
+    ("tomcat", "s3cret"),
+    ("manager", "manager"),
+    ("admin", "changeme"),
+    ("ubnt", "ubnt"),
+    ("pi", "raspberry"),
+];
@@ -0,0 +1,110 @@
+use std::net::{IpAddr, SocketAddr};
+
+use reqwest::{Client, Url};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::ACCEPT;
+use serde::Deserialize;
+
+use crate::Result;
+
+const A: u16 = 1;
+const AAAA: u16 = 28;
+
+/// Resolves hostnames with a DNS-over-HTTPS resolver that speaks the JSON API, such as
+/// `https://cloudflare-dns.com/dns-query`. The resolver's own hostname is resolved by the system.
+#[derive(Clone)]
+pub struct DohResolver {
+    endpoint: Url,
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Answer", default)]
+    answer: Vec<Answer>,
+}
+
+#[derive(Deserialize)]
+struct Answer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    data: String,
+}
+
+impl DohResolver {
+    pub fn new(endpoint: Url) -> Self {
+        Self { endpoint, client: Client::new() }
+    }
+
+    /// The IPv4 addresses of `name`, or its IPv6 addresses when it has none.
+    pub async fn lookup(&self, name: &str) -> Result<Vec<IpAddr>> {
+        for record_type in [A, AAAA] {
+            let body = self.client.get(self.endpoint.clone())
+                .query(&[("name", name), ("type", &record_type.to_string())])
+                .header(ACCEPT, "application/dns-json")
+                .send().await?
+                .error_for_status()?
+                .text().await?;
+            let response: DohResponse = serde_json::from_str(&body)?;
+
+            let ips: Vec<IpAddr> = response.answer.iter()
+                .filter(|a| a.record_type == record_type)
+                .filter_map(|a| a.data.parse().ok())
+                .collect();
+
+            if !ips.is_empty() {
+                return Ok(ips);
+            }
+        }
+
+        Err(format!("{name} did not resolve through {}", self.endpoint).into())
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+
+        Box::pin(async move {
+            let ips = resolver.lookup(name.as_str()).await?;
+            let addrs: Addrs = Box::new(ips.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use mockito::Matcher;
+    use reqwest::Url;
+
+    use crate::probe::doh::DohResolver;
+    use crate::Result;
+
+    #[tokio::test]
+    async fn lookup_through_doh_endpoint() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/dns-query")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("name".into(), "rustbuster.test".into()),
+                Matcher::UrlEncoded("type".into(), "1".into()),
+            ]))
+            .match_header("accept", "application/dns-json")
+            .with_body(r#"{"Status":0,"Answer":[{"name":"rustbuster.test","type":5,"data":"alias.test."},{"name":"alias.test","type":1,"TTL":300,"data":"192.0.2.7"}]}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/dns-query")
+            .match_query(Matcher::UrlEncoded("name".into(), "missing.test".into()))
+            .with_body(r#"{"Status":3}"#)
+            .create_async()
+            .await;
+
+        let resolver = DohResolver::new(Url::parse(&format!("{}/dns-query", server.url())).unwrap());
+
+        assert_eq!(resolver.lookup("rustbuster.test").await?, vec!["192.0.2.7".parse::<IpAddr>().unwrap()]);
+        assert!(resolver.lookup("missing.test").await.is_err());
+        Ok(())
+    }
+}
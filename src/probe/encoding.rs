@@ -0,0 +1,52 @@
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use reqwest::header::HeaderValue;
+
+/// The charset a `Content-Type` declares, if it names one `encoding_rs` knows.
+fn declared(content_type: Option<&HeaderValue>) -> Option<&'static Encoding> {
+    let content_type = content_type?.to_str().ok()?;
+
+    content_type.split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_, label)| Encoding::for_label(label.trim().trim_matches('"').as_bytes()))
+}
+
+/// The encoding a body is written in: its byte order mark, else the declared charset,
+/// else UTF-8 when it decodes as such, and windows-1252, the usual legacy default, when not.
+pub fn detect(content_type: Option<&HeaderValue>, body: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(body) {
+        return encoding;
+    }
+
+    match declared(content_type) {
+        Some(encoding) => encoding,
+        None if std::str::from_utf8(body).is_ok() => UTF_8,
+        None => WINDOWS_1252,
+    }
+}
+
+/// Decodes the body as its declared charset, or UTF-8 by default, unless a byte order mark says otherwise.
+pub fn decode(content_type: Option<&HeaderValue>, body: &[u8]) -> String {
+    declared(content_type).unwrap_or(UTF_8).decode(body).0.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use crate::probe::encoding::{decode, detect};
+
+    #[test]
+    fn detects_bom_then_charset_then_sniffs() {
+        let latin1 = HeaderValue::from_static("text/html; Charset=\"ISO-8859-1\"");
+
+        assert_eq!(detect(Some(&latin1), b"caf\xe9").name(), "windows-1252");
+        assert_eq!(detect(Some(&latin1), b"\xef\xbb\xbfcaf\xc3\xa9").name(), "UTF-8");
+        assert_eq!(detect(Some(&HeaderValue::from_static("text/html")), "café".as_bytes()).name(), "UTF-8");
+        assert_eq!(detect(None, b"caf\xe9").name(), "windows-1252");
+
+        assert_eq!(decode(Some(&latin1), b"caf\xe9"), "café");
+        assert_eq!(decode(None, b"caf\xe9"), "caf\u{fffd}");
+    }
+}
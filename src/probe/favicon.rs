@@ -0,0 +1,69 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// Shodan-style favicon hash: MurmurHash3 over the icon's base64 encoding, wrapped at 76 characters.
+pub fn hash(icon: &[u8]) -> i32 {
+    murmur3_32(encode_lines(icon).as_bytes(), 0) as i32
+}
+
+fn encode_lines(bytes: &[u8]) -> String {
+    STANDARD.encode(bytes)
+        .as_bytes()
+        .chunks(76)
+        .map(|line| format!("{}\n", String::from_utf8_lossy(line)))
+        .collect()
+}
+
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let scramble = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+    let mut chunks = data.chunks_exact(4);
+    let mut h = seed;
+
+    for chunk in chunks.by_ref() {
+        let k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        h = (h ^ scramble(k)).rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let k = tail.iter().rev().fold(0u32, |k, &b| (k << 8) | b as u32);
+        h ^= scramble(k);
+    }
+
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^ (h >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::probe::favicon::{encode_lines, hash, murmur3_32};
+
+    #[test]
+    fn murmur3_matches_reference_vectors() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"hello", 0), 0x248bfa47);
+        assert_eq!(murmur3_32(b"The quick brown fox jumps over the lazy dog", 0), 0x2e4ff723);
+    }
+
+    #[test]
+    fn encoding_wraps_lines_like_python_encodebytes() {
+        assert_eq!(encode_lines(b"rustbuster favicon"), "cnVzdGJ1c3RlciBmYXZpY29u\n");
+        assert_eq!(encode_lines(&[0; 60]).lines().map(|l| l.len()).collect::<Vec<_>>(), vec![76, 4]);
+    }
+
+    #[test]
+    fn hash_of_known_favicon_bytes() {
+        let icon: Vec<u8> = (0..=255).collect();
+
+        assert_eq!(hash(&icon), -757223386);
+        assert_eq!(hash(b"rustbuster favicon"), -1196815588);
+    }
+}
@@ -1,21 +1,76 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use reqwest::{Client, Method, StatusCode};
-use reqwest::header::{HeaderMap, HeaderName};
+use reqwest::{Client, Method, Request, StatusCode};
+use reqwest::header::{ALLOW, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, HeaderMap, HeaderName, HOST, LOCATION, TRANSFER_ENCODING, WWW_AUTHENTICATE};
+use rand::Rng;
+use serde::Serialize;
 use reqwest::Url;
+use tokio::sync::Semaphore;
 
 use crate::probe::builder::HttpProbeBuilder;
-use crate::Result;
+use crate::probe::connect_to::ConnectTo;
+use crate::probe::cookies::CookieJar;
+use crate::probe::digest::DigestAuth;
+use crate::probe::replace::Replacement;
+use crate::probe::signing::HmacSigner;
+use crate::probe::token::Token;
+use crate::{Error, Result};
+use crate::rng::SharedRng;
+use crate::words::Payload;
 
 pub mod builder;
+pub mod connect_to;
+pub mod cookies;
+pub mod creds;
+pub mod digest;
+mod doh;
+mod encoding;
+mod favicon;
+pub mod replace;
+mod robots;
+pub mod signing;
+mod token;
+mod waf;
 
-const FUZZ: &'static str = "FUZZ";
+pub const FUZZ: &str = "FUZZ";
+
+/// Characters of the body shown by `ProbeResponse::snippet`.
+const SNIPPET_LENGTH: usize = 80;
 
 pub struct HttpProbe {
     url: Url,
     client: Client,
+    default_headers: HeaderMap,
     method: Method,
     fuzzed_headers: HashMap<String, String>,
+    body: Option<String>,
+    signer: Option<HmacSigner>,
+    digest_auth: Option<DigestAuth>,
+    random_case: Option<SharedRng>,
+    replacements: Vec<Replacement>,
+    method_override: Option<Method>,
+    connections: Option<Semaphore>,
+    connect_to: Vec<ConnectTo>,
+    token: Option<(Arc<Token>, Duration)>,
+    follow_redirects: bool,
+    max_header_bytes: Option<usize>,
+    warn_path_rewrites: bool,
+    body_per_line: bool,
+    chunked: bool,
+    cookies: Option<CookieJar>,
+    path_rewritten: AtomicBool,
+}
+
+/// A request ready to send, with the parts of it `probe` reports on.
+struct PreparedRequest {
+    request_url: String,
+    send_url: String,
+    path: String,
+    method: Method,
+    request: Request,
 }
 
 impl HttpProbe {
@@ -23,73 +78,552 @@ impl HttpProbe {
         HttpProbeBuilder::new()
     }
 
-    pub async fn probe(&self, word: &str) -> Result<ProbeResponse> {
-        let request_url = self.url.as_str().replace(FUZZ, word);
-        let extra_headers = self.replace_keyword_in_headers(word)?;
+    /// Fetches the first bearer token and keeps refreshing it in the background, when a token endpoint is set.
+    pub async fn start_token_refresh(&self) -> Result<()> {
+        if let Some((token, interval)) = &self.token {
+            token.refresh().await?;
+            token.refresh_every(*interval);
+        }
+        Ok(())
+    }
+
+    /// Hash of the host's `/favicon.ico`, or `None` when it has none.
+    pub async fn favicon_hash(&self) -> Result<Option<i32>> {
+        let response = self.client.get(self.at_root("/favicon.ico")).send().await?;
 
-        let response = self.client
-            .request(self.method.clone(), &request_url)
-            .headers(extra_headers)
-            .send()
-            .await?;
+        match response.status().is_success() {
+            true => Ok(Some(favicon::hash(&response.bytes().await?))),
+            false => Ok(None),
+        }
+    }
+
+    /// Words for `keyword` taken from the paths disallowed in the host's `/robots.txt`
+    /// and listed in its sitemaps, relative to where `keyword` sits in the URL path.
+    pub async fn seed_words(&self, keyword: &str) -> Vec<String> {
+        let prefix = match self.url.path().find(keyword) {
+            Some(i) => self.url.path()[..i].to_string(),
+            None => return Vec::new(),
+        };
+
+        let (mut paths, sitemaps) = match self.fetch_text(self.at_root("/robots.txt")).await {
+            Some(robots) => robots::parse_robots(&robots),
+            None => (Vec::new(), Vec::new()),
+        };
 
+        let sitemaps = std::iter::once(self.at_root("/sitemap.xml"))
+            .chain(sitemaps.iter().filter_map(|s| self.url.join(s).ok()))
+            .filter(|s| s.host() == self.url.host());
+
+        for sitemap in sitemaps {
+            for loc in self.fetch_text(sitemap).await.map(|s| robots::parse_sitemap(&s)).unwrap_or_default() {
+                match Url::parse(&loc) {
+                    Ok(loc) if loc.host() == self.url.host() => paths.push(loc.path().to_string()),
+                    _ => (),
+                }
+            }
+        }
+
+        let mut words: Vec<String> = Vec::new();
+        for path in paths {
+            let word = match path.strip_prefix(&prefix) {
+                Some(word) => word.trim_end_matches('/'),
+                None => continue,
+            };
+            if !word.is_empty() && !words.iter().any(|w| w == word) {
+                words.push(word.to_string());
+            }
+        }
+        words
+    }
+
+    /// Name of the WAF or CDN the host's responses reveal, if any.
+    pub async fn detect_waf(&self) -> Result<Option<&'static str>> {
+        let response = self.client.get(self.at_root("/")).send().await?;
+
+        Ok(waf::detect(response.headers()))
+    }
+
+    /// Fails unless the base endpoint, the URL with an empty word, responds with the `expected` MIME type.
+    pub async fn health_check(&self, expected: &str) -> Result<()> {
+        let base = self.request_url(&Payload::from(""));
+        let response = self.client.get(&base).send().await?;
+        let content_type = response.headers().get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(str::trim)
+            .unwrap_or_default();
+
+        match content_type.eq_ignore_ascii_case(expected) {
+            true => Ok(()),
+            false => Err(format!("expected content type `{expected}` from {base} but got `{content_type}`; \
+                                  check that the URL points at the right endpoint").into()),
+        }
+    }
+
+    /// The methods `url` allows, from the `Allow` header of its response to OPTIONS.
+    pub async fn allowed_methods(&self, url: &str) -> Result<Vec<String>> {
+        let response = self.client.request(Method::OPTIONS, url).send().await?;
+
+        Ok(response.headers().get_all(ALLOW)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(|m| m.trim().to_ascii_uppercase())
+            .filter(|m| !m.is_empty())
+            .collect())
+    }
+
+    /// The status of `url` when requested with HTTP Basic credentials `user:pass`.
+    pub async fn status_with_credentials(&self, url: &str, user: &str, pass: &str) -> Result<StatusCode> {
+        let response = self.client.request(self.method.clone(), url).basic_auth(user, Some(pass)).send().await?;
+        Ok(response.status())
+    }
+
+    fn at_root(&self, path: &str) -> Url {
+        let mut url = self.url.clone();
+        url.set_path(path);
+        url.set_query(None);
+        url
+    }
+
+    /// The body of a successful GET, or `None` when the request fails.
+    async fn fetch_text(&self, url: Url) -> Option<String> {
+        let response = self.client.get(url).send().await.ok()?;
+
+        match response.status().is_success() {
+            true => response.text().await.ok(),
+            false => None,
+        }
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn request_url(&self, payload: &Payload) -> String {
+        payload.replace(self.url.as_str())
+    }
+
+    /// The request `probe` would send for `payload`, on one line: method, URL, headers and body.
+    pub fn dry_run(&self, payload: &Payload) -> Result<String> {
+        let request = self.build_request(payload)?.request;
+        let defaults = self.default_headers.iter().filter(|(k, _)| !request.headers().contains_key(*k));
+        let headers: Vec<String> = request.headers().iter().chain(defaults)
+            .map(|(k, v)| format!("{k}: {}", String::from_utf8_lossy(v.as_bytes())))
+            .collect();
+
+        let mut line = format!("{} {}", request.method(), request.url());
+        if !headers.is_empty() {
+            line.push_str(&format!(" [Headers: {}]", headers.join(", ")));
+        }
+        if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+            line.push_str(&format!(" [Body: {}]", String::from_utf8_lossy(body).escape_debug()));
+        }
+        Ok(line)
+    }
+
+    /// Builds the request for `payload`, as `probe` would send it.
+    fn build_request(&self, payload: &Payload) -> Result<PreparedRequest> {
+        let replacements: Vec<(String, String)> = self.replacements.iter()
+            .map(|r| (r.key.clone(), r.value()))
+            .collect();
+        let with_replacements = |payload: Payload| replacements.iter()
+            .fold(payload, |payload, (key, value)| payload.with(key, value));
+
+        let request_url = match &self.random_case {
+            Some(rng) => {
+                let mut rng = rng.lock().expect("rng lock poisoned");
+                self.request_url(&with_replacements(payload.map_words(|w| random_case(w, &mut *rng))))
+            }
+            None => self.request_url(&with_replacements(payload.clone())),
+        };
+        let payload_with_replacements = with_replacements(payload.clone());
+        let mut extra_headers = self.replace_keyword_in_headers(&payload_with_replacements)?;
+        let body = match self.body_per_line {
+            true => Some(payload.first_word().to_string()),
+            false => self.body.as_ref().map(|b| payload_with_replacements.replace(b)),
+        };
+
+        let path = Url::parse(&request_url)
+            .map(|u| u.query().map_or(u.path().to_string(), |q| format!("{}?{q}", u.path())))
+            .unwrap_or_default();
+
+        let method = match &self.method_override {
+            Some(verb) => {
+                extra_headers.insert(HeaderName::from_static("x-http-method-override"), verb.as_str().parse()?);
+                Method::GET
+            }
+            None => self.method.clone(),
+        };
+
+        if let Some(authorization) = self.token.as_ref().and_then(|(t, _)| t.authorization()) {
+            extra_headers.insert(AUTHORIZATION, authorization);
+        }
+
+        if let Some(signer) = &self.signer {
+            let signature = signer.sign(&method, &path, body.as_deref().unwrap_or_default());
+            extra_headers.insert(signer.header.clone(), signature.parse()?);
+        }
+
+        if let Some(jar) = &self.cookies {
+            let cookie = jar.header(extra_headers.get(COOKIE).or(self.default_headers.get(COOKIE)))?;
+            extra_headers.insert(COOKIE, cookie);
+        }
+
+        let send_url = self.connect_url(&request_url, &mut extra_headers)?;
+        let mut request = self.client
+            .request(method.clone(), &send_url)
+            .headers(extra_headers);
+
+        if let Some(body) = body {
+            if self.chunked {
+                // hyper honours a chunked Transfer-Encoding set by hand, and then drops the Content-Length.
+                request = request.header(TRANSFER_ENCODING, "chunked");
+            }
+            request = request.body(body);
+        }
+
+        Ok(PreparedRequest { request_url, send_url, path, method, request: request.build()? })
+    }
+
+    pub async fn probe(&self, payload: &Payload) -> Result<ProbeResponse> {
+        let PreparedRequest { request_url, send_url, path, method, request } = self.build_request(payload)?;
+        if self.warn_path_rewrites {
+            self.warn_if_rewritten(&send_url, request.url());
+        }
+        let retry = request.try_clone();
+        let mut raw_request = self.render_request(&request);
+        let _connection = match &self.connections {
+            Some(connections) => Some(connections.acquire().await.expect("connection semaphore is never closed")),
+            None => None,
+        };
+        let sent = Instant::now();
+        let response = self.client.execute(request).await.map_err(Error::from_http)?;
+
+        let response = match (&self.digest_auth, retry) {
+            (Some(digest_auth), Some(mut retry)) if response.status() == StatusCode::UNAUTHORIZED => {
+                let cnonce = format!("{:016x}", rand::random::<u64>());
+                let authorization = response.headers()
+                    .get_all(WWW_AUTHENTICATE)
+                    .iter()
+                    .filter_map(|challenge| challenge.to_str().ok())
+                    .find_map(|challenge| digest_auth.authorization(challenge, &method, &path, &cnonce));
+
+                match authorization {
+                    Some(authorization) => {
+                        retry.headers_mut().insert(AUTHORIZATION, authorization.parse()?);
+                        raw_request = self.render_request(&retry);
+                        self.client.execute(retry).await.map_err(Error::from_http)?
+                    }
+                    None => response,
+                }
+            }
+            _ => response,
+        };
+        let ttfb = sent.elapsed();
+
+        let request_url = match self.follow_redirects && Url::parse(&send_url).ok().as_ref() != Some(response.url()) {
+            true => response.url().to_string(),
+            false => request_url,
+        };
+        if let Some(jar) = &self.cookies {
+            jar.store(response.headers());
+        }
         let status_code = response.status();
-        let body = response.text().await.ok().unwrap_or_default();
+        let (headers, header_bytes) = cap_headers(response.headers(), self.max_header_bytes);
+        let header_content_length = response.headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u32>().ok());
+        let bytes = response.bytes().await.map_err(Error::from_http)?;
+        let response_time = sent.elapsed();
+        let encoding = encoding::detect(headers.get(CONTENT_TYPE), &bytes).name().to_string();
+        let body = encoding::decode(headers.get(CONTENT_TYPE), &bytes);
         let content_length = body.len() as u32;
 
         Ok(ProbeResponse {
             request_url,
+            word: payload.word(),
+            method,
             status_code,
             content_length,
+            header_content_length,
+            body_bytes: bytes.len() as u32,
+            headers,
             body,
+            raw_request,
+            response_time,
+            ttfb,
+            encoding,
+            oversized_headers: header_bytes,
         })
     }
 
-    fn replace_keyword_in_headers(&self, word: &str) -> Result<HeaderMap> {
+    /// Where to send the request for `request_url`, which differs when `--connect-to` redirects its port.
+    /// The Host header then keeps the original host and port.
+    fn connect_url(&self, request_url: &str, headers: &mut HeaderMap) -> Result<String> {
+        let url = match Url::parse(request_url) {
+            Ok(url) => url,
+            Err(_) => return Ok(request_url.to_string()),
+        };
+
+        match self.connect_to.iter().find(|c| c.matches(&url)) {
+            Some(connect_to) => {
+                let host = url.host_str().unwrap_or_default();
+                let host = url.port().map_or(host.to_string(), |port| format!("{host}:{port}"));
+                headers.insert(HOST, host.parse()?);
+                Ok(connect_to.redirect(&url).to_string())
+            }
+            None => Ok(request_url.to_string()),
+        }
+    }
+
+    /// Warns, the first time only, when the path sent for `raw` is not the one it spells out.
+    fn warn_if_rewritten(&self, raw: &str, url: &Url) {
+        let Some(target) = raw_target(raw) else { return };
+        let sent = url.query().map_or(url.path().to_string(), |q| format!("{}?{q}", url.path()));
+        if target != sent && !self.path_rewritten.swap(true, Ordering::Relaxed) {
+            eprintln!("Warning: {target} is sent as {sent}; only double slashes are kept as they are");
+        }
+    }
+
+    /// The request as sent, including the client's default headers.
+    pub fn render_request(&self, request: &Request) -> String {
+        let url = request.url();
+        let target = url.query().map_or(url.path().to_string(), |q| format!("{}?{q}", url.path()));
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let host = request.headers().get(HOST)
+            .map_or(host, |h| String::from_utf8_lossy(h.as_bytes()).to_string());
+
+        let defaults = self.default_headers.iter().filter(|(k, _)| !request.headers().contains_key(*k));
+        let headers: String = request.headers().iter().filter(|(k, _)| *k != HOST).chain(defaults)
+            .map(|(k, v)| format!("{k}: {}\n", String::from_utf8_lossy(v.as_bytes())))
+            .collect();
+        let body = request.body()
+            .and_then(|b| b.as_bytes())
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+
+        format!("{} {target} HTTP/1.1\nhost: {host}\n{headers}\n{body}", request.method())
+    }
+
+    fn replace_keyword_in_headers(&self, payload: &Payload) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
 
         for (k, v) in self.fuzzed_headers.iter() {
-            let key = k.replace(FUZZ, word);
-            let value = v.replace(FUZZ, word);
+            let key = payload.replace(k);
+            let value = payload.replace(v);
             headers.insert(HeaderName::from_bytes(key.as_bytes())?, value.parse()?);
         }
         Ok(headers)
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// The headers that fit in `max_bytes`, counted as they appear on the wire, and their full size when they do not all fit.
+fn cap_headers(headers: &HeaderMap, max_bytes: Option<usize>) -> (HeaderMap, Option<usize>) {
+    let max_bytes = match max_bytes {
+        Some(max_bytes) => max_bytes,
+        None => return (headers.clone(), None),
+    };
+
+    let mut capped = HeaderMap::new();
+    let mut used = 0;
+    for (name, value) in headers {
+        used += name.as_str().len() + value.len() + 4;
+        if used <= max_bytes {
+            capped.append(name, value.clone());
+        }
+    }
+
+    match used > max_bytes {
+        true => (capped, Some(used)),
+        false => (capped, None),
+    }
+}
+
+/// Single-quotes `s` for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// The path and query of `url` as written, before any parsing.
+fn raw_target(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let target = &rest[rest.find('/')?..];
+    Some(target.split('#').next().unwrap_or(target))
+}
+
+fn random_case(word: &str, rng: &mut impl Rng) -> String {
+    word.chars()
+        .map(|c| match rng.gen_bool(0.5) {
+            true => c.to_ascii_uppercase(),
+            false => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ProbeResponse {
     pub request_url: String,
+    pub word: String,
+    /// The method sent on the wire, which is GET when overridden by a header.
+    pub method: Method,
     pub status_code: StatusCode,
     pub content_length: u32,
+    pub header_content_length: Option<u32>,
+    /// The size of the body as downloaded, before it is decoded from its encoding.
+    pub body_bytes: u32,
+    pub headers: HeaderMap,
     pub body: String,
+    /// The request that produced this response, as rendered by `HttpProbe::render_request`.
+    pub raw_request: String,
+    /// From sending the request until the whole body was read.
+    pub response_time: Duration,
+    /// From sending the request until the response head arrived, before the body is read.
+    pub ttfb: Duration,
+    /// The character encoding the body was detected to be written in, like `UTF-8`.
+    pub encoding: String,
+    /// The size of the response headers when they exceeded the probe's budget, and `headers` only keeps the first of them.
+    pub oversized_headers: Option<usize>,
 }
 
 impl ProbeResponse {
-    pub fn display(&self, verbose: bool) -> String {
-        if verbose {
-            let url_path = Url::parse(self.request_url.as_str())
-                .map(|u| u.path().to_owned())
-                .unwrap_or_default();
+    /// Returns the declared `Content-Length` if it differs from the downloaded body size.
+    pub fn content_length_mismatch(&self) -> Option<u32> {
+        self.header_content_length.filter(|&l| l != self.body_bytes)
+    }
 
-            return format!("{:<30} ({:>10}) [Size: {:?}]",
-                           url_path,
-                           self.status_code,
-                           self.content_length);
+    /// The status line, headers and body.
+    pub fn render(&self) -> String {
+        let headers: String = self.headers.iter()
+            .map(|(k, v)| format!("{k}: {}\n", String::from_utf8_lossy(v.as_bytes())))
+            .collect();
+
+        format!("HTTP/1.1 {}\n{headers}\n{}", self.status_code, self.body)
+    }
+
+    /// The contents of the body's `<title>`, or empty when it has none.
+    pub fn title(&self) -> String {
+        let lower = self.body.to_ascii_lowercase();
+
+        lower.find("<title")
+            .and_then(|start| lower[start..].find('>').map(|end| start + end + 1))
+            .and_then(|start| lower[start..].find("</title>").map(|end| self.body[start..start + end].trim().to_string()))
+            .unwrap_or_default()
+    }
+
+    /// The start of the body on one line, without control characters, ending in an ellipsis when cut short.
+    pub fn snippet(&self) -> String {
+        let words: Vec<String> = self.body.split_whitespace()
+            .map(|w| w.chars().filter(|c| !c.is_control()).collect::<String>())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let collapsed = words.join(" ");
+
+        match collapsed.char_indices().nth(SNIPPET_LENGTH) {
+            Some((end, _)) => format!("{}…", &collapsed[..end]),
+            None => collapsed,
+        }
+    }
+
+    /// A `curl` command that sends the request again, built from `raw_request`.
+    pub fn curl(&self) -> String {
+        let (head, body) = self.raw_request.split_once("\n\n").unwrap_or((&self.raw_request, ""));
+        let mut lines = head.lines();
+        let method = lines.next().and_then(|l| l.split(' ').next()).unwrap_or("GET");
+
+        let mut command = format!("curl -X {method} {}", shell_quote(&self.request_url));
+        for header in lines.filter(|l| !l.starts_with("host:")) {
+            command.push_str(&format!(" -H {}", shell_quote(header)));
+        }
+        if !body.is_empty() {
+            command.push_str(&format!(" --data-binary {}", shell_quote(body)));
+        }
+        command
+    }
+
+    /// The URL of the directory this response is for: its own when it ends in `/`,
+    /// or where it redirects to when that adds a trailing `/` to its path.
+    pub fn directory(&self) -> Option<String> {
+        let url = Url::parse(&self.request_url).ok()?;
+        if url.path().ends_with('/') {
+            return Some(self.request_url.clone());
         }
-        self.request_url.clone()
+
+        let location = self.headers.get(LOCATION)?.to_str().ok()?;
+        let target = url.join(location).ok()?;
+        (target.path() == format!("{}/", url.path())).then(|| target.to_string())
+    }
+
+    /// The hit as a single line of JSON: `{"request_url":…,"status_code":200,"content_length":…,"word":…}`,
+    /// with a `case` field when its case sensitivity was confirmed.
+    pub fn to_json(&self, case: Option<&str>) -> Result<String> {
+        #[derive(Serialize)]
+        struct Hit<'a> {
+            request_url: &'a str,
+            status_code: u16,
+            content_length: u32,
+            word: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            case: Option<&'a str>,
+        }
+
+        serde_json::to_string(&Hit {
+            request_url: &self.request_url,
+            status_code: self.status_code.as_u16(),
+            content_length: self.content_length,
+            word: &self.word,
+            case,
+        }).map_err(|e| e.into())
+    }
+
+    /// The URL, then with each level of verbosity: its status and size, its timing and headers, and a body snippet.
+    pub fn display(&self, verbosity: u8) -> String {
+        if verbosity == 0 {
+            return self.request_url.clone();
+        }
+
+        let url_path = Url::parse(self.request_url.as_str())
+            .map(|u| u.path().to_owned())
+            .unwrap_or_default();
+        let mut line = format!("{:<30} ({:>10}) [Size: {:?}]", url_path, self.status_code, self.content_length);
+
+        if verbosity >= 2 {
+            let headers: Vec<String> = self.headers.iter()
+                .map(|(k, v)| format!("{k}: {}", String::from_utf8_lossy(v.as_bytes())))
+                .collect();
+            line.push_str(&format!(" [Time: {}ms] [TTFB: {}ms] [Headers: {}]",
+                                   self.response_time.as_millis(), self.ttfb.as_millis(), headers.join(", ")));
+        }
+        if verbosity >= 3 {
+            line.push_str(&format!("  {:?}", self.snippet()));
+        }
+        line
     }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use reqwest::header::USER_AGENT;
-    use reqwest::StatusCode;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, USER_AGENT};
+    use reqwest::{Method, StatusCode};
     use reqwest::Url;
 
-    use crate::probe::HttpProbe;
+    use crate::probe::{HttpProbe, ProbeResponse};
+    use crate::probe::signing::{HmacPart, HmacSigner};
     use crate::Result;
+    use crate::rng;
+    use crate::words::Payload;
 
     #[tokio::test]
     async fn fuzzer_gets_response() -> Result<()> {
@@ -99,16 +633,39 @@ mod tests {
             .with_body("hello")
             .create_async().await;
 
-        let url = Url::parse(&format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
 
         let fuzzer = HttpProbe::builder()
             .with_url(url)
             .build()?;
 
-        let r = fuzzer.probe("hello").await?;
+        let r = fuzzer.probe(&Payload::from("hello")).await?;
 
         assert_eq!(r.status_code, StatusCode::OK);
         assert_eq!(r.content_length, 5);
+        assert_eq!(r.word, "hello");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn probe_sends_cookies_and_keeps_the_ones_set() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/login")
+            .match_header("cookie", "session=abc")
+            .with_header("set-cookie", "csrf=123; Path=/")
+            .create_async().await;
+        let account = server.mock("GET", "/account")
+            .match_header("cookie", "session=abc; csrf=123")
+            .create_async().await;
+
+        let probe = HttpProbe::builder()
+            .with_url(Url::parse(&format!("{}/FUZZ", server.url())).unwrap())
+            .with_cookies(vec!["session=abc".parse()?])
+            .build()?;
+
+        assert_eq!(probe.probe(&Payload::from("login")).await?.status_code, StatusCode::OK);
+        assert_eq!(probe.probe(&Payload::from("account")).await?.status_code, StatusCode::OK);
+        account.assert_async().await;
         Ok(())
     }
 
@@ -120,16 +677,727 @@ mod tests {
             .create_async()
             .await;
 
-        let url = Url::parse(&format!("{}/do-fuzz", server.url()).as_str()).unwrap();
+        let url = Url::parse(format!("{}/do-fuzz", server.url()).as_str()).unwrap();
 
         let fuzzer = HttpProbe::builder()
             .with_url(url)
             .with_headers(vec![(USER_AGENT, "FUZZ".parse()?)])
             .build()?;
 
-        let r = fuzzer.probe("fill-to-header").await?;
+        let r = fuzzer.probe(&Payload::from("fill-to-header")).await?;
+
+        assert_eq!(r.status_code, StatusCode::OK);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mismatched_content_length_header_is_flagged() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("HEAD", "/mismatch")
+            .with_header("content-length", "100")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::HEAD)
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("mismatch")).await?;
+
+        assert_eq!(r.header_content_length, Some(100));
+        assert_eq!(r.content_length, 0);
+        assert_eq!(r.content_length_mismatch(), Some(100));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn decoded_body_is_not_flagged_as_mismatched() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/latin1")
+            .with_header("content-type", "text/html; charset=windows-1252")
+            .with_body(b"caf\xe9")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let r = HttpProbe::builder().with_url(url).build()?.probe(&Payload::from("latin1")).await?;
+
+        assert_eq!(r.body, "café");
+        assert_eq!((r.header_content_length, r.body_bytes), (Some(4), 4));
+        assert_eq!(r.content_length_mismatch(), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fuzzer_keywords_in_path_and_headers() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/admin")
+            .match_header(USER_AGENT.as_str(), "v2")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/P1", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_headers(vec![(USER_AGENT, "H1".parse()?)])
+            .with_keywords(vec!["P1".to_string(), "H1".to_string()])
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::default().with("P1", "admin").with("H1", "v2")).await?;
+
+        assert_eq!(r.status_code, StatusCode::OK);
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn favicon_hash_of_host() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/favicon.ico")
+            .with_body("rustbuster favicon")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/admin/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .build()?;
+
+        assert_eq!(fuzzer.favicon_hash().await?, Some(-1196815588));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn seed_words_from_robots_and_sitemap() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/robots.txt")
+            .with_body("User-agent: *\nDisallow: /app/private/\nDisallow: /cgi-bin/\nSitemap: /app-sitemap.xml\n")
+            .create_async()
+            .await;
+        server.mock("GET", "/sitemap.xml")
+            .with_status(404)
+            .create_async()
+            .await;
+        server.mock("GET", "/app-sitemap.xml")
+            .with_body(format!("<urlset><url><loc>{0}/app/about</loc></url><url><loc>{0}/app/private</loc></url></urlset>", server.url()))
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/app/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .build()?;
+
+        assert_eq!(fuzzer.seed_words("FUZZ").await, vec!["private", "about"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fuzzer_answers_digest_challenge() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/private")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_status(401)
+            .with_header("www-authenticate", r#"Digest realm="test", qop="auth", nonce="abc""#)
+            .create_async()
+            .await;
+        let authorized = server.mock("GET", "/private")
+            .match_header("authorization", mockito::Matcher::Regex(r#"^Digest username="user", realm="test", nonce="abc", uri="/private""#.to_string()))
+            .with_body("welcome")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_digest_auth(Some("user:pass".parse()?))
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("private")).await?;
+
+        assert_eq!(r.status_code, StatusCode::OK);
+        authorized.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resolves_host_through_doh() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/dns-query")
+            .match_query(mockito::Matcher::UrlEncoded("name".into(), "rustbuster.test".into()))
+            .with_body(r#"{"Status":0,"Answer":[{"name":"rustbuster.test","type":1,"data":"127.0.0.1"}]}"#)
+            .create_async()
+            .await;
+        let mock = server.mock("GET", "/admin")
+            .match_header("host", mockito::Matcher::Regex("^rustbuster.test:".to_string()))
+            .create_async()
+            .await;
+
+        let port = server.socket_address().port();
+        let url = Url::parse(&format!("http://rustbuster.test:{port}/FUZZ")).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_doh(Some(Url::parse(&format!("{}/dns-query", server.url())).unwrap()))
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("admin")).await?;
+
+        assert_eq!(r.status_code, StatusCode::OK);
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn max_connections_bounds_concurrent_sends() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let open = Arc::new(AtomicUsize::new(0));
+        let most_open = Arc::new(AtomicUsize::new(0));
+
+        let (open_, most_open_) = (open.clone(), most_open.clone());
+        thread::spawn(move || for stream in listener.incoming().flatten() {
+            let (open, most_open) = (open_.clone(), most_open_.clone());
+            thread::spawn(move || {
+                let mut stream = stream;
+                let _ = stream.read(&mut [0; 1024]);
+                most_open.fetch_max(open.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                open.fetch_sub(1, Ordering::SeqCst);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+            });
+        });
+
+        let fuzzer = Arc::new(HttpProbe::builder()
+            .with_url(Url::parse(&format!("http://127.0.0.1:{port}/FUZZ")).unwrap())
+            .with_max_connections(Some(2))
+            .build()?);
+
+        let probes: Vec<_> = (0..6)
+            .map(|i| {
+                let fuzzer = fuzzer.clone();
+                tokio::spawn(async move { fuzzer.probe(&Payload::from(i.to_string().as_str())).await })
+            })
+            .collect();
+        for probe in probes {
+            assert_eq!(probe.await??.status_code, StatusCode::OK);
+        }
+
+        assert_eq!(most_open.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn title_is_case_insensitive() {
+        let response = |body: &str| ProbeResponse { body: body.to_string(), ..Default::default() };
+
+        assert_eq!(response("<HTML><TITLE lang=en> Login </TITLE>").title(), "Login");
+        assert_eq!(response("no title").title(), "");
+    }
+
+    #[tokio::test]
+    async fn fuzz_header_sends_each_word_as_its_value() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for version in ["v1", "v2", "beta"] {
+            mocks.push(server.mock("GET", "/api")
+                .match_header("x-api-version", version)
+                .with_body(version)
+                .create_async()
+                .await);
+        }
+
+        let url = Url::parse(format!("{}/api", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_fuzz_header(Some(HeaderName::from_static("x-api-version")))
+            .build()?;
+
+        for version in ["v1", "v2", "beta"] {
+            let r = fuzzer.probe(&Payload::from(version)).await?;
+            assert_eq!(r.body, version);
+        }
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn connect_to_sends_elsewhere_but_keeps_host() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/admin")
+            .match_header("host", "rustbuster.test")
+            .create_async()
+            .await;
+
+        let port = server.socket_address().port();
+        let url = Url::parse("http://rustbuster.test/FUZZ").unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_connect_to(vec![format!("rustbuster.test:80:127.0.0.1:{port}").parse()?])
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("admin")).await?;
+
+        assert_eq!(r.status_code, StatusCode::OK);
+        assert_eq!(r.request_url, "http://rustbuster.test/admin");
+        assert!(r.raw_request.starts_with("GET /admin HTTP/1.1\nhost: rustbuster.test\n"), "{}", r.raw_request);
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[test]
+    fn snippet_is_collapsed_sanitized_and_truncated() {
+        let response = |body: &str| ProbeResponse { body: body.to_string(), ..Default::default() };
+
+        assert_eq!(response("  <h1>Hello</h1>\r\n\t<p>\u{1b}[31mworld\u{7}</p>\n").snippet(), "<h1>Hello</h1> <p>[31mworld</p>");
+        assert_eq!(response(&"é".repeat(100)).snippet(), format!("{}…", "é".repeat(80)));
+        assert_eq!(response(&"a".repeat(80)).snippet(), "a".repeat(80));
+    }
+
+    #[tokio::test]
+    async fn probe_follows_redirects_up_to_max() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/old")
+            .with_status(301)
+            .with_header("location", "/moved")
+            .create_async()
+            .await;
+        server.mock("GET", "/moved")
+            .with_status(302)
+            .with_header("location", "/new")
+            .create_async()
+            .await;
+        server.mock("GET", "/new")
+            .with_body("here")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let probe = |max| HttpProbe::builder().with_url(url.clone()).with_redirects(max).build();
+
+        let r = probe(0)?.probe(&Payload::from("old")).await?;
+        assert_eq!(r.status_code, StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(r.request_url, format!("{}/old", server.url()));
+
+        let r = probe(2)?.probe(&Payload::from("old")).await?;
+        assert_eq!(r.status_code, StatusCode::OK);
+        assert_eq!(r.request_url, format!("{}/new", server.url()));
+        assert_eq!(r.body, "here");
+
+        assert!(probe(1)?.probe(&Payload::from("old")).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn oversized_headers_are_capped() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/huge")
+            .with_header("x-small", "ok")
+            .with_header("x-padding", &"a".repeat(8 * 1024))
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let capped = HttpProbe::builder().with_url(url.clone()).with_max_header_bytes(Some(4096)).build()?;
+        let r = capped.probe(&Payload::from("huge")).await?;
+
+        assert!(r.oversized_headers.is_some_and(|n| n > 8 * 1024), "{:?}", r.oversized_headers);
+        assert!(r.headers.get("x-padding").is_none());
+        assert_eq!(r.headers.get("x-small").unwrap(), "ok");
+
+        let uncapped = HttpProbe::builder().with_url(url).build()?;
+        let r = uncapped.probe(&Payload::from("huge")).await?;
+        assert_eq!(r.oversized_headers, None);
+        assert!(r.headers.get("x-padding").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn display_adds_details_per_verbosity_level() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "text/html".parse().unwrap());
+        let response = ProbeResponse {
+            request_url: "http://localhost/admin".to_string(),
+            status_code: StatusCode::FORBIDDEN,
+            content_length: 22,
+            body: "<h1>Forbidden</h1>\n<p>Go away</p>".to_string(),
+            headers,
+            response_time: Duration::from_millis(15),
+            ttfb: Duration::from_millis(12),
+            ..Default::default()
+        };
+
+        assert_eq!(response.display(0), "http://localhost/admin");
+        assert_eq!(response.display(1), format!("{:<30} (403 Forbidden) [Size: 22]", "/admin"));
+        assert_eq!(response.display(2), format!("{:<30} (403 Forbidden) [Size: 22] [Time: 15ms] [TTFB: 12ms] [Headers: content-type: text/html]", "/admin"));
+        assert_eq!(response.display(3), format!("{}  \"<h1>Forbidden</h1> <p>Go away</p>\"", response.display(2)));
+    }
+
+    #[test]
+    fn to_json_has_hit_fields() -> Result<()> {
+        let response = ProbeResponse {
+            request_url: "http://localhost/admin?q=\"x\"".to_string(),
+            word: "admin".to_string(),
+            status_code: StatusCode::FORBIDDEN,
+            content_length: 12,
+            body: "not included".to_string(),
+            ..Default::default()
+        };
+
+        let json = response.to_json(None)?;
+        assert_eq!(json, r#"{"request_url":"http://localhost/admin?q=\"x\"","status_code":403,"content_length":12,"word":"admin"}"#);
+        assert!(!json.contains('\n'));
+        assert!(response.to_json(Some("case-sensitive"))?.ends_with(r#""word":"admin","case":"case-sensitive"}"#));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn chunked_sends_body_with_chunked_transfer_encoding() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/upload")
+            .match_header("transfer-encoding", "chunked")
+            .match_header("content-length", mockito::Matcher::Missing)
+            .match_body("name=admin")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/upload", server.url()).as_str()).unwrap();
+        let probe = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::POST)
+            .with_body(Some("name=FUZZ".to_string()))
+            .with_chunked(true)
+            .build()?;
+
+        let response = probe.probe(&Payload::from("admin")).await?;
+        assert_eq!(response.status_code, StatusCode::OK);
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn body_per_line_posts_each_word_as_body() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let injection = server.mock("POST", "/api")
+            .match_body(r#"{"user": {"$ne": null}}"#)
+            .with_status(200)
+            .create_async()
+            .await;
+        server.mock("POST", "/api").with_status(400).create_async().await;
+
+        let url = Url::parse(format!("{}/api", server.url()).as_str()).unwrap();
+        let probe = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::POST)
+            .with_body_per_line(true)
+            .build()?;
+
+        let hit = probe.probe(&Payload::from(r#"{"user": {"$ne": null}}"#)).await?;
+        assert_eq!(hit.status_code, StatusCode::OK);
+        let miss = probe.probe(&Payload::from(r#"{"user": "admin"}"#)).await?;
+        assert_eq!(miss.status_code, StatusCode::BAD_REQUEST);
+        injection.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn warn_path_rewrites_flags_dot_segments_but_not_double_slashes() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let config = server.mock("GET", "/admin//config")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let probe = |warn: bool| HttpProbe::builder().with_url(url.clone()).with_warn_path_rewrites(warn).build();
+
+        let warning = probe(true)?;
+        let response = warning.probe(&Payload::from("admin//config")).await?;
+        assert_eq!(response.status_code, StatusCode::OK);
+        assert!(!warning.path_rewritten.load(Ordering::Relaxed));
+        config.assert_async().await;
+
+        warning.probe(&Payload::from("admin/./config")).await?;
+        assert!(warning.path_rewritten.load(Ordering::Relaxed));
+
+        let quiet = probe(false)?;
+        quiet.probe(&Payload::from("admin/./config")).await?;
+        assert!(!quiet.path_rewritten.load(Ordering::Relaxed));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn probe_detects_encoding() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/utf8")
+            .with_header("content-type", "text/html")
+            .with_body("café")
+            .create_async()
+            .await;
+        server.mock("GET", "/sjis")
+            .with_header("content-type", "text/html; charset=Shift_JIS")
+            .with_body(b"\x93\xfa\x96\x7b")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let fuzzer = HttpProbe::builder().with_url(url).build()?;
+
+        let utf8 = fuzzer.probe(&Payload::from("utf8")).await?;
+        assert_eq!(utf8.encoding, "UTF-8");
+        assert_eq!(utf8.body, "café");
+
+        let sjis = fuzzer.probe(&Payload::from("sjis")).await?;
+        assert_eq!(sjis.encoding, "Shift_JIS");
+        assert_eq!(sjis.body, "日本");
+        Ok(())
+    }
+
+    /// A server that answers one request with its head at once, and its 4-byte body `slow` after 300ms.
+    fn slow_body_server() -> Result<Url> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        thread::spawn(move || {
+            if let Some(Ok(mut stream)) = listener.incoming().next() {
+                let _ = stream.read(&mut [0; 1024]);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 4\r\n\r\n");
+                let _ = stream.flush();
+                thread::sleep(Duration::from_millis(300));
+                let _ = stream.write_all(b"slow");
+            }
+        });
+        Ok(Url::parse(&format!("http://127.0.0.1:{port}/FUZZ")).unwrap())
+    }
+
+    #[tokio::test]
+    async fn ttfb_excludes_slow_body() -> Result<()> {
+        let fuzzer = HttpProbe::builder().with_url(slow_body_server()?).build()?;
+
+        let r = fuzzer.probe(&Payload::from("admin")).await?;
+
+        assert_eq!(r.body, "slow");
+        assert!(r.ttfb < Duration::from_millis(200), "{:?}", r.ttfb);
+        assert!(r.response_time >= Duration::from_millis(300), "{:?}", r.response_time);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn body_that_stalls_past_the_timeout_times_out() -> Result<()> {
+        let fuzzer = HttpProbe::builder().with_url(slow_body_server()?).with_timeout(Duration::from_millis(150)).build()?;
+
+        let r = fuzzer.probe(&Payload::from("admin")).await;
+
+        assert!(matches!(r, Err(crate::Error::Timeout)), "{r:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn zero_timeout_waits_indefinitely() -> Result<()> {
+        let fuzzer = HttpProbe::builder().with_url(slow_body_server()?).with_timeout(Duration::ZERO).build()?;
+
+        assert_eq!(fuzzer.probe(&Payload::from("admin")).await?.body, "slow");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn health_check_compares_content_type() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/api/")
+            .with_header("content-type", "application/json; charset=utf-8")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/api/FUZZ", server.url()).as_str()).unwrap();
+        let fuzzer = HttpProbe::builder().with_url(url).build()?;
+
+        assert!(fuzzer.health_check("application/json").await.is_ok());
+        match fuzzer.health_check("application/xml").await {
+            Err(crate::Error::Custom(e)) => assert!(e.contains("expected content type `application/xml`") && e.contains("got `application/json`"), "{e}"),
+            _ => panic!("expected a content type mismatch"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn token_refresh_updates_authorization() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let issued = Arc::new(AtomicUsize::new(0));
+        let counter = issued.clone();
+        server.mock("POST", "/token")
+            .with_body_from_request(move |_| {
+                let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                format!(r#"{{"access_token":"token-{n}","token_type":"Bearer"}}"#).into()
+            })
+            .create_async()
+            .await;
+        server.mock("GET", "/admin")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_token_endpoint(Some(Url::parse(&format!("{}/token", server.url())).unwrap()), Duration::from_millis(100))
+            .build()?;
+        fuzzer.start_token_refresh().await?;
+
+        let first = fuzzer.probe(&Payload::from("admin")).await?;
+        assert!(first.raw_request.contains("\nauthorization: Bearer token-1\n"), "{}", first.raw_request);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let later = fuzzer.probe(&Payload::from("admin")).await?;
+        assert!(issued.load(Ordering::SeqCst) > 1);
+        assert!(!later.raw_request.contains("token-1"), "{}", later.raw_request);
+        assert!(later.raw_request.contains("\nauthorization: Bearer token-"), "{}", later.raw_request);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn curl_reproduces_request() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("POST", "/admin")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::POST)
+            .with_headers(vec![(HeaderName::from_static("x-api-key"), "it's secret".parse()?)])
+            .with_body(Some("user=FUZZ".to_string()))
+            .build()?;
+
+        let curl = fuzzer.probe(&Payload::from("admin")).await?.curl();
+
+        assert!(curl.starts_with(&format!("curl -X POST '{}/admin'", server.url())), "{curl}");
+        assert!(curl.contains(r#" -H 'x-api-key: it'\''s secret'"#), "{curl}");
+        assert!(curl.ends_with(" --data-binary 'user=admin'"), "{curl}");
+        assert!(!curl.contains("host:"), "{curl}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn random_case_changes_url_case_but_keeps_word() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", mockito::Matcher::Regex("(?i)^/administrator$".to_string()))
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_random_case(Some(rng::shared(Some(7))))
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("administrator")).await?;
+        let expected = format!("{}/administrator", server.url());
+
+        assert_ne!(r.request_url, expected);
+        assert!(r.request_url.eq_ignore_ascii_case(&expected));
+        assert_eq!(r.word, "administrator");
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fuzzer_applies_replacements_to_body() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/login")
+            .match_body("user=admin&csrf=abc123")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/login", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::POST)
+            .with_body(Some("user=FUZZ&csrf=TOKEN".to_string()))
+            .with_replacements(vec!["TOKEN=abc123".parse()?])
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("admin")).await?;
+
+        assert_eq!(r.word, "admin");
+        mock.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn detect_cloudflare_from_cf_ray() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/")
+            .with_status(403)
+            .with_header("cf-ray", "8a1b2c3d4e5f6a7b-AMS")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .build()?;
+
+        assert_eq!(fuzzer.detect_waf().await?, Some("Cloudflare"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn method_override_sends_get_with_header() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/admin")
+            .match_header("x-http-method-override", "DELETE")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/FUZZ", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::POST)
+            .with_method_override(Some(Method::DELETE))
+            .build()?;
+
+        let r = fuzzer.probe(&Payload::from("admin")).await?;
 
         assert_eq!(r.status_code, StatusCode::OK);
+        mock.assert_async().await;
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn fuzzer_sends_body_and_signature() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("POST", "/sign")
+            .match_body("what do ya want for nothing?")
+            .match_header("x-signature", "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+            .create_async()
+            .await;
+
+        let url = Url::parse(format!("{}/sign", server.url()).as_str()).unwrap();
+
+        let fuzzer = HttpProbe::builder()
+            .with_url(url)
+            .with_method(Method::POST)
+            .with_body(Some("what do ya want for FUZZ?".to_string()))
+            .with_signer(Some(HmacSigner::new(HeaderName::from_static("x-signature"), "Jefe", vec![HmacPart::Body])))
+            .build()?;
+
+        fuzzer.probe(&Payload::from("nothing")).await?;
+
+        mock.assert_async().await;
+        Ok(())
+    }
+}
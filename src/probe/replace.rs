@@ -0,0 +1,79 @@
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Error;
+
+/// A placeholder replaced in the URL, headers and body of every request, next to the fuzz keywords.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replacement {
+    pub key: String,
+    value: Value,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Static(String),
+    /// Seconds since the Unix epoch when the request is sent.
+    Now,
+    /// A fresh random (version 4) UUID per request.
+    Uuid,
+}
+
+impl FromStr for Replacement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = match s.split_once('=') {
+            Some((key, value)) if !key.is_empty() => (key, value),
+            _ => return Err(format!("invalid replacement `{s}`: expected KEY=VALUE").into()),
+        };
+
+        let value = match value {
+            "{{now}}" => Value::Now,
+            "{{uuid}}" => Value::Uuid,
+            value => Value::Static(value.to_string()),
+        };
+
+        Ok(Replacement { key: key.to_string(), value })
+    }
+}
+
+impl Replacement {
+    pub fn value(&self) -> String {
+        match &self.value {
+            Value::Static(value) => value.clone(),
+            Value::Now => SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().to_string(),
+            Value::Uuid => uuid_v4(rand::random()),
+        }
+    }
+}
+
+fn uuid_v4(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::probe::replace::{Replacement, uuid_v4};
+
+    #[test]
+    fn parse_replacements() {
+        let token: Replacement = "TOKEN=abc=123".parse().unwrap();
+
+        assert_eq!(token.key, "TOKEN");
+        assert_eq!(token.value(), "abc=123");
+        assert!("TS={{now}}".parse::<Replacement>().unwrap().value().parse::<u64>().is_ok());
+        assert!("TOKEN".parse::<Replacement>().is_err());
+        assert!("=abc".parse::<Replacement>().is_err());
+    }
+
+    #[test]
+    fn uuid_has_version_and_variant() {
+        assert_eq!(uuid_v4([0xff; 16]), "ffffffff-ffff-4fff-bfff-ffffffffffff");
+        assert_eq!(uuid_v4([0; 16]), "00000000-0000-4000-8000-000000000000");
+    }
+}
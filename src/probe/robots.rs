@@ -0,0 +1,66 @@
+/// The paths disallowed by a robots.txt, and the sitemaps it lists.
+///
+/// Rules with wildcards are skipped, since they do not name a single path.
+pub fn parse_robots(robots: &str) -> (Vec<String>, Vec<String>) {
+    let mut disallowed = Vec::new();
+    let mut sitemaps = Vec::new();
+
+    for line in robots.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let (directive, value) = match line.split_once(':') {
+            Some((directive, value)) => (directive.trim().to_ascii_lowercase(), value.trim()),
+            None => continue,
+        };
+
+        match directive.as_str() {
+            "disallow" if value.starts_with('/') && !value.contains('*') => {
+                disallowed.push(value.trim_end_matches('$').to_string())
+            }
+            "sitemap" if !value.is_empty() => sitemaps.push(value.to_string()),
+            _ => (),
+        }
+    }
+
+    (disallowed, sitemaps)
+}
+
+/// The `<loc>` URLs listed in a sitemap.
+pub fn parse_sitemap(sitemap: &str) -> Vec<String> {
+    sitemap.split("<loc>")
+        .skip(1)
+        .filter_map(|s| s.split_once("</loc>"))
+        .map(|(loc, _)| loc.trim().replace("&amp;", "&"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::probe::robots::{parse_robots, parse_sitemap};
+
+    #[test]
+    fn robots_disallowed_paths_and_sitemaps() {
+        let robots = "User-agent: *\n\
+                      Disallow: /admin/ # keep out\n\
+                      disallow: /backup.zip$\n\
+                      Disallow: /*.php\n\
+                      Disallow:\n\
+                      Allow: /public\n\
+                      Sitemap: https://example.com/sitemap-pages.xml\n";
+
+        let (disallowed, sitemaps) = parse_robots(robots);
+
+        assert_eq!(disallowed, vec!["/admin/", "/backup.zip"]);
+        assert_eq!(sitemaps, vec!["https://example.com/sitemap-pages.xml"]);
+    }
+
+    #[test]
+    fn sitemap_locations() {
+        let sitemap = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://example.com/about</loc></url>
+              <url><loc> https://example.com/search?q=a&amp;p=2 </loc></url>
+            </urlset>"#;
+
+        assert_eq!(parse_sitemap(sitemap), vec!["https://example.com/about", "https://example.com/search?q=a&p=2"]);
+    }
+}
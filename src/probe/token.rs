@@ -0,0 +1,62 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use reqwest::{Client, Url};
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+use tokio::time;
+
+use crate::Result;
+
+/// A bearer token from an OAuth-style token endpoint, refreshed before it expires during long scans.
+pub struct Token {
+    endpoint: Url,
+    client: Client,
+    authorization: RwLock<Option<HeaderValue>>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+impl Token {
+    pub fn new(endpoint: Url, client: Client) -> Self {
+        Self { endpoint, client, authorization: RwLock::new(None) }
+    }
+
+    /// The `Authorization` header for the latest token, once one was fetched.
+    pub fn authorization(&self) -> Option<HeaderValue> {
+        self.authorization.read().expect("token lock poisoned").clone()
+    }
+
+    /// POSTs to the token endpoint and keeps the `access_token` of its JSON response.
+    pub async fn refresh(&self) -> Result<()> {
+        let body = self.client.post(self.endpoint.clone())
+            .send().await?
+            .error_for_status()?
+            .text().await?;
+        let token: TokenResponse = serde_json::from_str(&body)?;
+
+        *self.authorization.write().expect("token lock poisoned") = Some(format!("Bearer {}", token.access_token).parse()?);
+        Ok(())
+    }
+
+    /// Refreshes the token every `interval` until it is dropped, keeping the previous one when a refresh fails.
+    pub fn refresh_every(self: &Arc<Self>, interval: Duration) {
+        let token = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match token.upgrade() {
+                    Some(token) => if let Err(e) = token.refresh().await {
+                        eprintln!("Warning: token refresh failed, keeping the previous token: {e:?}");
+                    },
+                    None => break,
+                }
+            }
+        });
+    }
+}
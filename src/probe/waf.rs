@@ -0,0 +1,62 @@
+use reqwest::header::{HeaderMap, SERVER, SET_COOKIE};
+
+/// Traces a WAF or CDN leaves in a response: headers it adds, its `Server` header, or cookie names it sets.
+struct Signature {
+    name: &'static str,
+    headers: &'static [&'static str],
+    servers: &'static [&'static str],
+    cookies: &'static [&'static str],
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature { name: "Cloudflare", headers: &["cf-ray", "cf-cache-status"], servers: &["cloudflare"], cookies: &["__cf_bm", "__cfduid", "cf_clearance"] },
+    Signature { name: "Sucuri", headers: &["x-sucuri-id", "x-sucuri-cache"], servers: &["sucuri"], cookies: &[] },
+    Signature { name: "Imperva Incapsula", headers: &["x-iinfo"], servers: &[], cookies: &["incap_ses_", "visid_incap_"] },
+    Signature { name: "Akamai", headers: &["x-akamai-transformed"], servers: &["akamaighost"], cookies: &["ak_bmsc"] },
+    Signature { name: "AWS CloudFront", headers: &["x-amz-cf-id", "x-amz-cf-pop"], servers: &["cloudfront"], cookies: &["awsalb"] },
+    Signature { name: "Azure Front Door", headers: &["x-azure-ref"], servers: &[], cookies: &[] },
+    Signature { name: "Fastly", headers: &["x-fastly-request-id"], servers: &[], cookies: &[] },
+    Signature { name: "F5 BIG-IP", headers: &[], servers: &["bigip"], cookies: &["bigipserver", "ts01"] },
+    Signature { name: "ModSecurity", headers: &[], servers: &["mod_security"], cookies: &[] },
+];
+
+/// The name of the first WAF or CDN whose signature matches the response headers.
+pub fn detect(headers: &HeaderMap) -> Option<&'static str> {
+    let server = headers.get(SERVER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let cookies: Vec<String> = headers.get_all(SET_COOKIE).iter()
+        .filter_map(|v| v.to_str().ok())
+        .map(|v| v.to_ascii_lowercase())
+        .collect();
+
+    SIGNATURES.iter()
+        .find(|s| s.headers.iter().any(|h| headers.contains_key(*h)) ||
+            s.servers.iter().any(|name| server.contains(name)) ||
+            s.cookies.iter().any(|name| cookies.iter().any(|c| c.starts_with(name))))
+        .map(|s| s.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderMap, HeaderValue, SERVER, SET_COOKIE};
+
+    use crate::probe::waf::detect;
+
+    #[test]
+    fn detect_by_server_or_cookie() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(detect(&headers), None);
+
+        headers.insert(SERVER, HeaderValue::from_static("nginx"));
+        assert_eq!(detect(&headers), None);
+
+        headers.insert(SET_COOKIE, HeaderValue::from_static("visid_incap_123=abc; path=/"));
+        assert_eq!(detect(&headers), Some("Imperva Incapsula"));
+
+        headers.remove(SET_COOKIE);
+        headers.insert(SERVER, HeaderValue::from_static("AkamaiGHost"));
+        assert_eq!(detect(&headers), Some("Akamai"));
+    }
+}
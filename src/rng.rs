@@ -0,0 +1,15 @@
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// The random number generator behind everything randomized in a scan, so one seed reproduces all of it.
+pub type SharedRng = Arc<Mutex<StdRng>>;
+
+pub fn shared(seed: Option<u64>) -> SharedRng {
+    let rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    Arc::new(Mutex::new(rng))
+}
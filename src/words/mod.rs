@@ -1,12 +1,249 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::process::Stdio;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
 
 use crate::{Error, Result};
+use crate::probe::FUZZ;
+
+/// Suffixes `--backups` appends to each word, as editors and admins leave them behind.
+pub const BACKUP_SUFFIXES: &[&str] = &[".bak", ".old", ".orig", ".save", ".swp", ".tmp", "~", ".1"];
+
+/// Registered `/.well-known/` locations that `--well-known` probes before the wordlist.
+pub const WELL_KNOWN: &[&str] = &[
+    ".well-known/security.txt",
+    ".well-known/openid-configuration",
+    ".well-known/oauth-authorization-server",
+    ".well-known/jwks.json",
+    ".well-known/assetlinks.json",
+    ".well-known/apple-app-site-association",
+    ".well-known/change-password",
+    ".well-known/host-meta",
+    ".well-known/webfinger",
+    ".well-known/nodeinfo",
+    ".well-known/mta-sts.txt",
+    ".well-known/acme-challenge/",
+    ".well-known/pki-validation/",
+    ".well-known/ai-plugin.json",
+];
+
+/// Default cap on the memory taken by words held in memory, in bytes.
+pub const DEFAULT_MAX_MEMORY: usize = 512 * 1024 * 1024;
+
+const YEARS: &[&str] = &["2023", "2024"];
+const LEET: &[(char, char)] = &[('a', '4'), ('e', '3'), ('i', '1'), ('o', '0'), ('s', '5'), ('t', '7')];
+const SEPARATORS: &[char] = &['-', '_', '.'];
+
+/// A rule set that derives variants from each word, e.g. for credential or subdomain fuzzing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mutation {
+    /// Appends recent years: `admin2023`, `admin2024`.
+    Years,
+    /// Swaps letters for look-alike digits: `4dm1n`.
+    Leet,
+    /// Swaps a separator for the other common ones: `my-app` gives `my_app` and `my.app`.
+    Separators,
+}
+
+impl FromStr for Mutation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "years" => Ok(Mutation::Years),
+            "leet" => Ok(Mutation::Leet),
+            "separators" => Ok(Mutation::Separators),
+            other => Err(format!("invalid mutation `{other}`: expected years, leet or separators").into()),
+        }
+    }
+}
+
+impl Mutation {
+    /// The variants of `word` this rule derives, which may be none.
+    pub fn apply(&self, word: &str) -> Vec<String> {
+        match self {
+            Mutation::Years => YEARS.iter().map(|year| format!("{word}{year}")).collect(),
+            Mutation::Leet => {
+                let leet: String = word.chars()
+                    .map(|c| LEET.iter().find(|(from, _)| *from == c.to_ascii_lowercase()).map_or(c, |(_, to)| *to))
+                    .collect();
+                vec![leet]
+            }
+            Mutation::Separators => match word.chars().find(|c| SEPARATORS.contains(c)) {
+                Some(separator) => SEPARATORS.iter()
+                    .filter(|s| **s != separator)
+                    .map(|s| word.replace(separator, &s.to_string()))
+                    .collect(),
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
+/// The order in which the words of a source are probed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Order {
+    /// As they appear in the source, which is streamed.
+    #[default]
+    File,
+    ShortestFirst,
+    LongestFirst,
+}
+
+impl FromStr for Order {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "file" => Ok(Order::File),
+            "shortest-first" => Ok(Order::ShortestFirst),
+            "longest-first" => Ok(Order::LongestFirst),
+            other => Err(format!("invalid order `{other}`: expected file, shortest-first or longest-first").into()),
+        }
+    }
+}
+
+/// A sed-like `s/FIND/REPLACE/` substitution applied to each word, replacing every match with a trailing `g`.
+/// `\1` or `$1` in REPLACE insert a capture group, and `&` the whole match.
+#[derive(Clone, Debug)]
+pub struct WordSub {
+    find: Regex,
+    replace: String,
+    global: bool,
+}
+
+impl FromStr for WordSub {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::from(format!("invalid word substitution `{s}`: expected s/FIND/REPLACE/ or s/FIND/REPLACE/g"));
+
+        let rest = s.strip_prefix('s').ok_or_else(invalid)?;
+        let delimiter = rest.chars().next().filter(|c| !c.is_alphanumeric() && *c != '\\').ok_or_else(invalid)?;
+
+        match split_unescaped(&rest[delimiter.len_utf8()..], delimiter)[..] {
+            [ref find, ref replace, ref flags] if flags.is_empty() || flags == "g" => Ok(WordSub {
+                find: Regex::new(find).map_err(|e| Error::from(format!("invalid word substitution `{s}`: {e}")))?,
+                replace: sed_replacement(replace),
+                global: flags == "g",
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl WordSub {
+    pub fn apply(&self, word: &str) -> String {
+        match self.global {
+            true => self.find.replace_all(word, self.replace.as_str()).into_owned(),
+            false => self.find.replace(word, self.replace.as_str()).into_owned(),
+        }
+    }
+}
+
+/// Splits `s` on `delimiter`, where a backslash-escaped delimiter stands for itself.
+fn split_unescaped(s: &str, delimiter: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next) if next == delimiter => parts.last_mut().unwrap().push(next),
+                Some(next) => parts.last_mut().unwrap().extend([c, next]),
+                None => parts.last_mut().unwrap().push(c),
+            },
+            c if c == delimiter => parts.push(String::new()),
+            c => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+/// A sed replacement in the syntax of `Regex::replace`.
+fn sed_replacement(replace: &str) -> String {
+    let mut replacement = String::new();
+    let mut chars = replace.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(d) if d.is_ascii_digit() => replacement.push_str(&format!("${{{d}}}")),
+                Some(other) => replacement.push(other),
+                None => replacement.push(c),
+            },
+            '&' => replacement.push_str("${0}"),
+            '$' if !chars.peek().is_some_and(|n| n.is_ascii_digit() || *n == '{') => replacement.push_str("$$"),
+            c => replacement.push(c),
+        }
+    }
+    replacement
+}
+
+/// Collects `words`, failing as soon as their approximate size exceeds `max_memory` bytes.
+fn buffer(words: impl IntoIterator<Item=String>, max_memory: usize) -> Result<Vec<String>> {
+    let mut used = 0;
+    let mut buffered = Vec::new();
+
+    for word in words {
+        used += word.len() + std::mem::size_of::<String>();
+        if used > max_memory {
+            return Err(format!("buffered words take more than the {max_memory} bytes allowed by --max-wordlist-memory; \
+                                read them from a file, which is streamed, instead").into());
+        }
+        buffered.push(word);
+    }
+
+    Ok(buffered)
+}
+
+/// `word` followed by its variants under `mutations`, without duplicates, and sorted when `stable`.
+fn mutate(word: String, mutations: &[Mutation], stable: bool) -> Vec<String> {
+    let mut variants: Vec<String> = mutations.iter().flat_map(|m| m.apply(&word)).collect();
+    if stable {
+        variants.sort();
+    }
+    let mut words = vec![word];
+    for variant in variants {
+        if !words.contains(&variant) {
+            words.push(variant);
+        }
+    }
+    words
+}
 
 pub struct Wordlist {
-    filename: PathBuf,
+    source: Source,
+    /// Words probed before those of the source, which skips them if it has them too.
+    seeds: Vec<String>,
+    mutations: Vec<Mutation>,
+    subs: Vec<WordSub>,
     extensions: Vec<String>,
+    /// Cap on the memory of the words buffered rather than streamed from the source.
+    max_memory: usize,
+    /// Sort what does not come from the source in order, so every run iterates identically.
+    stable_order: bool,
+    /// Decode lines that are not valid UTF-8 lossily instead of skipping them.
+    lossy: bool,
+    /// Character counts of the words kept, before mutations and extensions.
+    word_length: RangeInclusive<usize>,
+    /// The words of the source, buffered when they are probed in another order than theirs.
+    ordered: Option<Vec<String>>,
+    /// How many words there are before extensions, counted once as reading them may be slow.
+    word_count: OnceLock<usize>,
+}
+
+enum Source {
+    File(PathBuf),
+    /// The stdout of a shell command, whose lines are the words; it is run once and its output kept for every pass.
+    Output(Vec<u8>),
 }
 
 impl TryFrom<PathBuf> for Wordlist {
@@ -18,13 +255,42 @@ impl TryFrom<PathBuf> for Wordlist {
         }
 
         Ok(Wordlist {
-            filename,
+            source: Source::File(filename),
+            seeds: Vec::new(),
+            mutations: Vec::new(),
+            subs: Vec::new(),
             extensions: vec![String::default()],
+            max_memory: DEFAULT_MAX_MEMORY,
+            stable_order: false,
+            lossy: false,
+            word_length: 0..=usize::MAX,
+            ordered: None,
+            word_count: OnceLock::new(),
         })
     }
 }
 
 impl Wordlist {
+    /// Reads words from the output of a shell command, which must exit successfully and print at most `max_memory` bytes.
+    /// The command runs once, and its output is kept for every pass over the list.
+    pub async fn from_command(command: &str, max_memory: usize) -> Result<Self> {
+        let output = run(command, max_memory).await?;
+
+        Ok(Wordlist {
+            source: Source::Output(output),
+            seeds: Vec::new(),
+            mutations: Vec::new(),
+            subs: Vec::new(),
+            extensions: vec![String::default()],
+            max_memory,
+            stable_order: false,
+            lossy: false,
+            word_length: 0..=usize::MAX,
+            ordered: None,
+            word_count: OnceLock::new(),
+        })
+    }
+
     pub fn set_extensions(&mut self, extensions: Vec<String>) {
         self.extensions = extensions.iter()
             .map(|ext| if ext.is_empty() {
@@ -35,30 +301,377 @@ impl Wordlist {
             .collect();
     }
 
+    /// Also expands each word with these suffixes, appended as they are.
+    pub fn add_suffixes(&mut self, suffixes: &[&str]) {
+        self.extensions.extend(suffixes.iter().map(|s| s.to_string()));
+    }
+
+    pub fn set_seeds(&mut self, seeds: Vec<String>) -> Result<()> {
+        self.word_count.take();
+        self.seeds = buffer(seeds, self.max_memory)?;
+        if self.stable_order {
+            self.seeds.sort();
+        }
+        Ok(())
+    }
+
+    pub fn set_stable_order(&mut self, stable_order: bool) {
+        self.stable_order = stable_order;
+    }
+
+    pub fn set_lossy(&mut self, lossy: bool) {
+        self.word_count.take();
+        self.lossy = lossy;
+    }
+
+    pub fn set_max_memory(&mut self, max_memory: usize) {
+        self.max_memory = max_memory;
+    }
+
+    /// Buffers the words of the source to probe them by length, within the memory cap; `Order::File` streams them.
+    /// Set it last, as the words are read with the settings at that point.
+    pub fn set_order(&mut self, order: Order) -> Result<()> {
+        self.word_count.take();
+        self.ordered = None;
+        if order == Order::File {
+            return Ok(());
+        }
+
+        let mut words = buffer(self.source_words(), self.max_memory)?;
+        match order {
+            Order::LongestFirst => words.sort_by_key(|w| std::cmp::Reverse(w.chars().count())),
+            _ => words.sort_by_key(|w| w.chars().count()),
+        }
+        self.ordered = Some(words);
+        Ok(())
+    }
+
+    /// Skips words shorter than `min` or longer than `max` characters.
+    pub fn set_word_length(&mut self, min: Option<usize>, max: Option<usize>) {
+        self.word_count.take();
+        self.word_length = min.unwrap_or(0)..=max.unwrap_or(usize::MAX);
+    }
+
+    pub fn set_mutations(&mut self, mutations: Vec<Mutation>) {
+        self.word_count.take();
+        self.mutations = mutations;
+    }
+
+    /// Rewrites each word with these substitutions in turn, before mutations and extensions.
+    pub fn set_word_subs(&mut self, subs: Vec<WordSub>) {
+        self.word_count.take();
+        self.subs = subs;
+    }
+
     pub fn iter(&self) -> impl Iterator<Item=String> + '_ {
-        let file = File::open(&self.filename).expect("exists");
+        self.words()
+            .flat_map(move |w| self.extensions.iter().map(move |ext| format!("{w}{ext}")))
+    }
+
+    /// The words of the source, each followed by its mutations, without extensions.
+    pub fn words(&self) -> Box<dyn Iterator<Item=String> + '_> {
+        let seeds = self.seeds.iter().cloned();
+        let words = self.source_words().filter(|w| !self.seeds.contains(w));
+        let words = seeds.chain(words)
+            .map(|w| self.subs.iter().fold(w, |w, sub| sub.apply(&w)))
+            .filter(|w| self.word_length.contains(&w.chars().count()));
+
+        match self.mutations.is_empty() {
+            true => Box::new(words),
+            false => Box::new(words.flat_map(|w| mutate(w, &self.mutations, self.stable_order))),
+        }
+    }
+
+    fn source_words(&self) -> Box<dyn Iterator<Item=String> + '_> {
+        if let Some(ordered) = &self.ordered {
+            return Box::new(ordered.iter().cloned());
+        }
+        Box::new(self.source_lines().filter_map(|line| decode(line, self.lossy)))
+    }
 
-        BufReader::new(file).lines()
-            .map(|w| w.unwrap_or_default())
-            .flat_map(move |w| self.extensions.iter()
-                .map(|ext| format!("{w}{ext}"))
-                .collect::<Vec<String>>())
+    /// The lines of the source as bytes, without line endings.
+    fn source_lines(&self) -> Box<dyn Iterator<Item=Vec<u8>> + '_> {
+        let lines: Box<dyn Iterator<Item=Vec<u8>>> = match &self.source {
+            Source::File(filename) => {
+                let file = File::open(filename).expect("exists");
+
+                Box::new(BufReader::new(file).split(b'\n')
+                    .map(|w| w.unwrap_or_default()))
+            }
+            Source::Output(output) => {
+                let output = output.strip_suffix(b"\n").unwrap_or(output);
+                match output.is_empty() {
+                    true => Box::new(std::iter::empty()),
+                    false => Box::new(output.split(|b| *b == b'\n').map(<[u8]>::to_vec)),
+                }
+            }
+        };
+
+        Box::new(lines.map(|mut line| {
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            line
+        }))
     }
 
+    /// Lines of the source that are not valid UTF-8, which are skipped unless decoded lossily.
+    pub fn invalid_lines(&self) -> usize {
+        self.source_lines().filter(|line| std::str::from_utf8(line).is_err()).count()
+    }
+
+    /// The number of words `iter` yields, without expanding their extensions.
     pub fn len(&self) -> usize {
-        self.iter().count()
+        self.word_count() * self.extensions.len()
+    }
+
+    /// The number of words `words` yields; the source is only read for it once.
+    pub fn word_count(&self) -> usize {
+        *self.word_count.get_or_init(|| self.words().count())
+    }
+}
+
+/// Runs `command` in a shell and returns its stdout, once it exited successfully within `max_memory` bytes of output.
+async fn run(command: &str, max_memory: usize) -> Result<Vec<u8>> {
+    let mut child = Command::new("sh").arg("-c").arg(command).stdout(Stdio::piped()).spawn()?;
+    let mut output = Vec::new();
+    child.stdout.take().expect("stdout is piped").take(max_memory as u64 + 1).read_to_end(&mut output).await?;
+
+    if output.len() > max_memory {
+        let _ = child.kill().await;
+        return Err(format!("input command `{command}` printed more than the {max_memory} bytes allowed by --max-wordlist-memory; \
+                            write its output to a file and read that with -w instead").into());
+    }
+
+    let status = child.wait().await?;
+    match status.success() {
+        true => Ok(output),
+        false => Err(format!("input command `{command}` failed: {status}").into()),
+    }
+}
+
+/// A line as a word, or `None` when it is not valid UTF-8 and not decoded `lossy`.
+fn decode(line: Vec<u8>, lossy: bool) -> Option<String> {
+    match String::from_utf8(line) {
+        Ok(word) => Some(word),
+        Err(e) if lossy => Some(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        Err(_) => None,
     }
 }
 
+/// The words substituted into a single request, one per fuzz keyword.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Payload(Vec<(String, String)>);
+
+impl From<&str> for Payload {
+    fn from(word: &str) -> Self {
+        Payload::default().with(FUZZ, word)
+    }
+}
+
+impl Payload {
+    pub fn with(mut self, keyword: &str, word: &str) -> Self {
+        self.0.push((keyword.to_string(), word.to_string()));
+        self
+    }
+
+    /// The substituted words, joined when there is more than one keyword.
+    pub fn word(&self) -> String {
+        self.0.iter()
+            .map(|(_, word)| word.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    }
+
+    /// The word of the first keyword.
+    pub fn first_word(&self) -> &str {
+        self.0.first().map_or("", |(_, word)| word)
+    }
+
+    /// Appends an extension to the word of the first keyword, which is the one extensions expand.
+    pub fn with_extension(&self, extension: &str) -> Self {
+        let mut payload = self.clone();
+        if let Some((_, word)) = payload.0.first_mut() {
+            word.push_str(extension);
+        }
+        payload
+    }
+
+    /// Prepends a directory to the word of the first keyword, which is the one fuzzing the path.
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        let mut payload = self;
+        if let Some((_, word)) = payload.0.first_mut().filter(|_| !prefix.is_empty()) {
+            word.insert_str(0, prefix);
+        }
+        payload
+    }
+
+    pub fn map_words(&self, mut f: impl FnMut(&str) -> String) -> Self {
+        Payload(self.0.iter().map(|(keyword, word)| (keyword.clone(), f(word))).collect())
+    }
+
+    pub fn replace(&self, s: &str) -> String {
+        self.0.iter()
+            .fold(s.to_string(), |s, (keyword, word)| s.replace(keyword, word))
+    }
+}
+
+/// Wordlists mapped to their fuzz keyword. Iterating yields every combination of their words.
+pub struct Wordlists {
+    wordlists: Vec<(String, Wordlist)>,
+}
+
+impl TryFrom<Vec<(String, PathBuf)>> for Wordlists {
+    type Error = Error;
+
+    fn try_from(filenames: Vec<(String, PathBuf)>) -> Result<Self> {
+        filenames.into_iter()
+            .map(|(keyword, filename)| Ok((keyword, Wordlist::try_from(filename)?)))
+            .collect::<Result<Vec<(String, Wordlist)>>>()?
+            .try_into()
+    }
+}
+
+impl TryFrom<Vec<(String, Wordlist)>> for Wordlists {
+    type Error = Error;
+
+    fn try_from(keyword_wordlists: Vec<(String, Wordlist)>) -> Result<Self> {
+        let mut wordlists: Vec<(String, Wordlist)> = Vec::new();
+
+        for (keyword, wordlist) in keyword_wordlists {
+            if wordlists.iter().any(|(k, _)| k == &keyword) {
+                return Err(format!("keyword `{keyword}` is assigned more than one wordlist").into());
+            }
+            wordlists.push((keyword, wordlist));
+        }
+
+        Ok(Wordlists { wordlists })
+    }
+}
+
+impl Wordlists {
+    pub fn keywords(&self) -> Vec<String> {
+        self.wordlists.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    /// Seeds only go to the first wordlist, which is normally the one fuzzing the path.
+    pub fn set_seeds(&mut self, seeds: Vec<String>) -> Result<()> {
+        match self.wordlists.first_mut() {
+            Some((_, wordlist)) => wordlist.set_seeds(seeds),
+            None => Ok(()),
+        }
+    }
+
+    pub fn set_max_memory(&mut self, max_memory: usize) {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_max_memory(max_memory);
+        }
+    }
+
+    pub fn set_stable_order(&mut self, stable_order: bool) {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_stable_order(stable_order);
+        }
+    }
+
+    pub fn set_lossy(&mut self, lossy: bool) {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_lossy(lossy);
+        }
+    }
+
+    pub fn set_word_length(&mut self, min: Option<usize>, max: Option<usize>) {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_word_length(min, max);
+        }
+    }
+
+    pub fn invalid_lines(&self) -> usize {
+        self.wordlists.iter().map(|(_, w)| w.invalid_lines()).sum()
+    }
+
+    pub fn set_order(&mut self, order: Order) -> Result<()> {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_order(order)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_word_subs(&mut self, subs: Vec<WordSub>) {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_word_subs(subs.clone());
+        }
+    }
+
+    /// Unlike extensions, mutations expand every wordlist.
+    pub fn set_mutations(&mut self, mutations: Vec<Mutation>) {
+        for (_, wordlist) in self.wordlists.iter_mut() {
+            wordlist.set_mutations(mutations.clone());
+        }
+    }
+
+    /// Extensions only expand the first wordlist, which is normally the one fuzzing the path.
+    pub fn set_extensions(&mut self, extensions: Vec<String>) {
+        if let Some((_, wordlist)) = self.wordlists.first_mut() {
+            wordlist.set_extensions(extensions);
+        }
+    }
+
+    pub fn add_suffixes(&mut self, suffixes: &[&str]) {
+        if let Some((_, wordlist)) = self.wordlists.first_mut() {
+            wordlist.add_suffixes(suffixes);
+        }
+    }
+
+    /// The `WELL_KNOWN` locations as words of the first keyword.
+    pub fn well_known(&self) -> Vec<Payload> {
+        match self.wordlists.first() {
+            Some((keyword, _)) => WELL_KNOWN.iter().map(|w| Payload::default().with(keyword, w)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Extensions of the first wordlist, not counting the bare word.
+    pub fn extensions(&self) -> Vec<String> {
+        self.wordlists.first()
+            .map(|(_, w)| w.extensions.iter().filter(|ext| !ext.is_empty()).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> Box<dyn Iterator<Item=Payload> + '_> {
+        self.combine(|w| Box::new(w.iter()))
+    }
+
+    /// Like `iter`, but without expanding extensions.
+    pub fn iter_bare(&self) -> Box<dyn Iterator<Item=Payload> + '_> {
+        self.combine(|w| Box::new(w.words()))
+    }
+
+    fn combine<'a>(&'a self, words: fn(&'a Wordlist) -> Box<dyn Iterator<Item=String> + 'a>) -> Box<dyn Iterator<Item=Payload> + 'a> {
+        self.wordlists.iter()
+            .fold(Box::new(std::iter::once(Payload::default())), |payloads, (keyword, wordlist)| {
+                Box::new(payloads.flat_map(move |payload| words(wordlist)
+                    .map(move |w| payload.clone().with(keyword, &w))))
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.wordlists.iter().map(|(_, w)| w.len()).product()
+    }
+
+    pub fn len_bare(&self) -> usize {
+        self.wordlists.iter().map(|(_, w)| w.word_count()).product()
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use std::fs::{File, remove_file};
+    use std::fs::{File, read_to_string, remove_file};
     use std::io::prelude::*;
     use std::path::PathBuf;
 
     use crate::Result;
-    use crate::words::Wordlist;
+    use crate::words::{BACKUP_SUFFIXES, DEFAULT_MAX_MEMORY, Mutation, Order, Payload, Wordlist, Wordlists, WordSub};
 
     #[test]
     fn wordlist_can_iterate() -> Result<()> {
@@ -103,4 +716,275 @@ mod tests {
 
         remove_file(filename).map_err(|e| e.into())
     }
+
+    #[test]
+    fn wordlist_skips_words_out_of_length() -> Result<()> {
+        let filename = "wordlist_skips_words_out_of_length.txt";
+        let mut file = File::create(filename)?;
+        file.write_all("a\nme\nlet\ncafé\nadministrator".as_bytes())?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_word_length(Some(2), Some(4));
+        wordlist.set_extensions(vec!["".to_string(), "php".to_string()]);
+
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["me", "me.php", "let", "let.php", "café", "café.php"]);
+        assert_eq!(wordlist.len(), 6);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_applies_word_substitutions() -> Result<()> {
+        let filename = "wordlist_applies_word_substitutions.txt";
+        let mut file = File::create(filename)?;
+        file.write_all(b"foo_bar\nfoo_bar_baz\nadmin")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_word_subs(vec!["s/_/-/".parse()?]);
+        wordlist.set_extensions(vec!["".to_string(), "php".to_string()]);
+
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec![
+            "foo-bar", "foo-bar.php", "foo-bar_baz", "foo-bar_baz.php", "admin", "admin.php",
+        ]);
+        assert_eq!(wordlist.len(), 6);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn parse_word_substitutions() -> Result<()> {
+        assert_eq!("s/_/-/g".parse::<WordSub>()?.apply("foo_bar_baz"), "foo-bar-baz");
+        assert_eq!(r"s/(\w+)-(\w+)/\2_$1/".parse::<WordSub>()?.apply("user-admin"), "admin_user");
+        assert_eq!(r"s|/|\||".parse::<WordSub>()?.apply("a/b"), "a|b");
+        assert_eq!("s/^/&$/".parse::<WordSub>()?.apply("admin"), "$admin");
+        assert_eq!("s/min/[&]/".parse::<WordSub>()?.apply("admin"), "ad[min]");
+
+        assert!("s/_/-".parse::<WordSub>().is_err());
+        assert!("y/_/-/".parse::<WordSub>().is_err());
+        assert!("s/_/-/x".parse::<WordSub>().is_err());
+        assert!("s/(/-/".parse::<WordSub>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn wordlist_orders_words_by_length() -> Result<()> {
+        let filename = "wordlist_orders_words_by_length.txt";
+        let mut file = File::create(filename)?;
+        file.write_all(b"admin\napi\nbackup\nv1\nlogin")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_order(Order::ShortestFirst)?;
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["v1", "api", "admin", "login", "backup"]);
+
+        wordlist.set_order(Order::LongestFirst)?;
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["backup", "admin", "login", "api", "v1"]);
+
+        wordlist.set_order(Order::File)?;
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["admin", "api", "backup", "v1", "login"]);
+
+        wordlist.set_max_memory(16);
+        assert!(wordlist.set_order(Order::ShortestFirst).is_err());
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_len_matches_streamed_words_of_large_file() -> Result<()> {
+        let filename = "wordlist_len_matches_streamed_words_of_large_file.txt";
+        let mut file = std::io::BufWriter::new(File::create(filename)?);
+        for i in 0..200_000 {
+            writeln!(file, "word{i}")?;
+        }
+        file.flush()?;
+        drop(file);
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_extensions(vec!["".to_string(), "php".to_string()]);
+        wordlist.set_max_memory(1024);
+
+        assert_eq!(wordlist.len(), 400_000);
+        assert_eq!(wordlist.iter().count(), wordlist.len());
+        assert_eq!(wordlist.iter().nth(399_999), Some("word199999.php".to_string()));
+
+        wordlist.set_word_length(None, Some(5));
+        assert_eq!(wordlist.len(), 20);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_expands_backup_suffixes() -> Result<()> {
+        let filename = "wordlist_expands_backup_suffixes.txt";
+        let mut file = File::create(filename)?;
+        file.write_all(b"index.php")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.add_suffixes(BACKUP_SUFFIXES);
+
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec![
+            "index.php", "index.php.bak", "index.php.old", "index.php.orig", "index.php.save",
+            "index.php.swp", "index.php.tmp", "index.php~", "index.php.1",
+        ]);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_expands_mutations() -> Result<()> {
+        let filename = "wordlist_expands_mutations.txt";
+        let mut file = File::create(filename)?;
+        file.write_all(b"admin\nmy-app")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_mutations(vec![Mutation::Years, Mutation::Leet, Mutation::Separators]);
+        wordlist.set_extensions(vec!["".to_string(), "php".to_string()]);
+
+        let words: Vec<String> = wordlist.words().collect();
+        assert_eq!(words, vec![
+            "admin", "admin2023", "admin2024", "4dm1n",
+            "my-app", "my-app2023", "my-app2024", "my-4pp", "my_app", "my.app",
+        ]);
+        assert_eq!(wordlist.len(), 20);
+        assert_eq!(wordlist.iter().nth(1), Some("admin.php".to_string()));
+        assert!("typos".parse::<Mutation>().is_err());
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_stable_order_iterates_identically() -> Result<()> {
+        let filename = "wordlist_stable_order_iterates_identically.txt";
+        File::create(filename)?.write_all(b"admin\nmy-app")?;
+
+        let iterate = || -> Result<Vec<String>> {
+            let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+            wordlist.set_stable_order(true);
+            wordlist.set_mutations(vec![Mutation::Separators, Mutation::Leet, Mutation::Years]);
+            wordlist.set_extensions(vec!["".to_string(), "php".to_string()]);
+            wordlist.set_seeds(vec!["login".to_string(), "about".to_string()])?;
+            Ok(wordlist.iter().collect())
+        };
+
+        let first = iterate()?;
+        assert_eq!(first.join("\n").as_bytes(), iterate()?.join("\n").as_bytes());
+        assert_eq!(first[..6], ["about", "about.php", "4b0u7", "4b0u7.php", "about2023", "about2023.php"]);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_seeds_come_first_without_duplicates() -> Result<()> {
+        let filename = "wordlist_seeds_come_first_without_duplicates.txt";
+        let mut file = File::create(filename)?;
+        file.write_all(b"let\nme\nin")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_seeds(vec!["admin".to_string(), "me".to_string()])?;
+
+        assert_eq!(wordlist.len(), 4);
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["admin", "me", "let", "in"]);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_buffer_over_max_memory_errors() -> Result<()> {
+        let filename = "wordlist_buffer_over_max_memory_errors.txt";
+        File::create(filename)?.write_all(b"let\nme\nin")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+        wordlist.set_max_memory(64);
+
+        assert!(wordlist.set_seeds(vec!["admin".to_string()]).is_ok());
+        match wordlist.set_seeds(vec!["admin".to_string(); 10]) {
+            Err(crate::Error::Custom(e)) => assert!(e.contains("--max-wordlist-memory"), "{e}"),
+            _ => panic!("expected the memory guard to trigger"),
+        }
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn wordlist_skips_or_decodes_invalid_utf8() -> Result<()> {
+        let filename = "wordlist_skips_or_decodes_invalid_utf8.txt";
+        File::create(filename)?.write_all(b"admin\r\nca\xffe\nlogin")?;
+
+        let mut wordlist = Wordlist::try_from(PathBuf::from(filename))?;
+
+        assert_eq!(wordlist.invalid_lines(), 1);
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["admin", "login"]);
+        assert_eq!(wordlist.len(), 2);
+
+        wordlist.set_lossy(true);
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["admin", "ca\u{fffd}e", "login"]);
+
+        remove_file(filename).map_err(|e| e.into())
+    }
+
+    #[tokio::test]
+    async fn wordlist_from_command_output() -> Result<()> {
+        let wordlist = Wordlist::from_command("printf 'a\\nb\\nc\\n'", DEFAULT_MAX_MEMORY).await?;
+
+        assert_eq!(wordlist.len(), 3);
+        assert_eq!(wordlist.iter().collect::<Vec<String>>(), vec!["a", "b", "c"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn wordlist_from_command_runs_it_once() -> Result<()> {
+        let counter = "wordlist_from_command_runs_it_once.txt";
+        let command = format!("echo run >> {counter}; printf 'a\\nb\\n'");
+        let wordlist = Wordlist::from_command(&command, DEFAULT_MAX_MEMORY).await?;
+
+        assert_eq!(wordlist.invalid_lines(), 0);
+        assert_eq!(wordlist.len(), 2);
+        assert_eq!(wordlist.iter().count(), 2);
+        assert_eq!(wordlist.iter().count(), 2);
+        assert_eq!(read_to_string(counter)?, "run\n");
+        remove_file(counter).map_err(|e| e.into())
+    }
+
+    #[tokio::test]
+    async fn wordlist_from_failing_command_errors() {
+        assert!(Wordlist::from_command("exit 3", DEFAULT_MAX_MEMORY).await.is_err());
+        assert!(Wordlist::from_command("printf 'a\\n'; exit 3", DEFAULT_MAX_MEMORY).await.is_err());
+        assert!(Wordlist::from_command("rustbuster-command-that-does-not-exist", DEFAULT_MAX_MEMORY).await.is_err());
+        assert!(Wordlist::from_command("printf 'abcdef\\n'", 4).await.is_err());
+    }
+
+    #[test]
+    fn wordlists_iterate_every_combination() -> Result<()> {
+        let paths = "wordlists_iterate_every_combination_paths.txt";
+        let values = "wordlists_iterate_every_combination_values.txt";
+        File::create(paths)?.write_all(b"admin\nlogin")?;
+        File::create(values)?.write_all(b"1\n2")?;
+
+        let wordlists = Wordlists::try_from(vec![
+            ("P1".to_string(), PathBuf::from(paths)),
+            ("H1".to_string(), PathBuf::from(values)),
+        ])?;
+
+        assert_eq!(wordlists.len(), 4);
+
+        let mut payloads = wordlists.iter();
+
+        assert_eq!(payloads.next(), Some(Payload::default().with("P1", "admin").with("H1", "1")));
+        assert_eq!(payloads.next(), Some(Payload::default().with("P1", "admin").with("H1", "2")));
+        assert_eq!(payloads.next(), Some(Payload::default().with("P1", "login").with("H1", "1")));
+        assert_eq!(payloads.next(), Some(Payload::default().with("P1", "login").with("H1", "2")));
+        assert_eq!(payloads.next(), None);
+
+        remove_file(paths)?;
+        remove_file(values).map_err(|e| e.into())
+    }
+
+    #[test]
+    fn payload_replaces_every_keyword() {
+        let payload = Payload::default().with("P1", "admin").with("H1", "v2");
+
+        assert_eq!(payload.replace("/P1?version=H1"), "/admin?version=v2");
+        assert_eq!(payload.word(), "admin, v2");
+        assert_eq!(payload.with_extension(".php").word(), "admin.php, v2");
+        assert_eq!(payload.with_prefix("api/").word(), "api/admin, v2");
+    }
 }